@@ -1,10 +1,11 @@
-use criterion::{criterion_group, criterion_main, Criterion};
-use hdlc::{decode, encode, SpecialChars};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use hdlc::fcs::{Fcs16, Fcs32};
+use hdlc::{decode, decode_slice, encode, encode_into, encode_with_fcs, Fcs, SpecialChars};
 
 fn bench_encode_megabyte(c: &mut Criterion) {
     let bytes = Box::new(vec![0u8; 1_000_000]);
     c.bench_function("bench_encode_megabyte", move |b| {
-        b.iter(|| encode(&*bytes, SpecialChars::default()))
+        b.iter(|| encode(&bytes, SpecialChars::default()))
     });
 }
 
@@ -13,19 +14,19 @@ fn bench_decode_megabyte(c: &mut Criterion) {
     bytes[0] = 0x7E;
     bytes[999_999] = 0x7E;
     c.bench_function("bench_decode_megabyte", move |b| {
-        b.iter(|| decode(&*bytes, SpecialChars::default()))
+        b.iter(|| decode(&bytes, SpecialChars::default()))
     });
 }
 
 fn bench_encode_special_chars_megabyte(c: &mut Criterion) {
-    let bytes = Box::new(vec![0x7E as u8; 1_000_000]);
+    let bytes = Box::new(vec![0x7E_u8; 1_000_000]);
     c.bench_function("bench_encode_special_chars_megabyte", move |b| {
-        b.iter(|| encode(&*bytes, SpecialChars::default()))
+        b.iter(|| encode(&bytes, SpecialChars::default()))
     });
 }
 
 fn bench_decode_special_chars_2_megabytes(c: &mut Criterion) {
-    let mut bytes = Box::new(vec![0x7D as u8; 2_000_000]);
+    let mut bytes = Box::new(vec![0x7D_u8; 2_000_000]);
     let mut num = 1;
 
     // Make the vector [0x5E, 0x7D, 0x5E, 0x7D, 0x5E, ... ].  Add sync after
@@ -42,26 +43,85 @@ fn bench_decode_special_chars_2_megabytes(c: &mut Criterion) {
     bytes[0] = 0x7E;
     bytes[1_999_999] = 0x7E;
     c.bench_function("bench_decode_special_chars_2_megabytes", move |b| {
-        b.iter(|| decode(&*bytes, SpecialChars::default()))
+        b.iter(|| decode(&bytes, SpecialChars::default()))
     });
 }
 
-// fn bench_decode_slice_megabyte(c: &mut Criterion) {
+fn bench_decode_slice_megabyte(c: &mut Criterion) {
+    let mut bytes = vec![0u8; 1_000_000];
+    bytes[0] = 0x7E;
+    bytes[999_999] = 0x7E;
+    c.bench_function("bench_decode_slice_megabyte", move |b| {
+        b.iter_batched_ref(
+            || bytes.clone(),
+            |input| decode_slice(input, SpecialChars::default()).map(|out| out.len()),
+            BatchSize::LargeInput,
+        )
+    });
+}
 
-//     let mut bytes = Box::new(&mut [0u8; 1_000_000]);
-//     bytes[0] = 0x7E;
-//     bytes[999_999] = 0x7E;
+fn bench_encode_into_megabyte(c: &mut Criterion) {
+    let bytes = vec![0u8; 1_000_000];
+    let mut output = vec![0u8; 2_000_002];
+    c.bench_function("bench_encode_into_megabyte", move |b| {
+        b.iter(|| encode_into(&bytes, &mut output, SpecialChars::default()))
+    });
+}
 
-// //     b.iter(|| decode_slice(*bytes, SpecialChars::default()) )
-//     c.bench_function("bench_decode_slice_megabyte", move |b| b.iter(|| decode_slice(*bytes, SpecialChars::default())));
-// }
+fn bench_encode_mixed_megabyte(c: &mut Criterion) {
+    // One special byte roughly every 64 bytes, closer to real traffic than all or nothing
+    let bytes: Vec<u8> = (0..1_000_000u32)
+        .map(|i| if i % 64 == 63 { 0x7E } else { i as u8 & 0x3F })
+        .collect();
+    c.bench_function("bench_encode_mixed_megabyte", move |b| {
+        b.iter(|| encode(&bytes, SpecialChars::default()))
+    });
+}
+
+fn bench_decode_mixed_megabyte(c: &mut Criterion) {
+    let payload: Vec<u8> = (0..1_000_000u32)
+        .map(|i| if i % 64 == 63 { 0x7E } else { i as u8 & 0x3F })
+        .collect();
+    let bytes = encode(&payload, SpecialChars::default()).unwrap();
+    c.bench_function("bench_decode_mixed_megabyte", move |b| {
+        b.iter(|| decode(&bytes, SpecialChars::default()))
+    });
+}
+
+fn bench_fcs16_megabyte(c: &mut Criterion) {
+    let bytes = vec![0x5Au8; 1_000_000];
+    c.bench_function("bench_fcs16_megabyte", move |b| {
+        b.iter(|| Fcs16::checksum(&bytes))
+    });
+}
+
+fn bench_fcs32_megabyte(c: &mut Criterion) {
+    let bytes = vec![0x5Au8; 1_000_000];
+    c.bench_function("bench_fcs32_megabyte", move |b| {
+        b.iter(|| Fcs32::checksum(&bytes))
+    });
+}
+
+fn bench_encode_fcs16_megabyte(c: &mut Criterion) {
+    let bytes = vec![0u8; 1_000_000];
+    c.bench_function("bench_encode_fcs16_megabyte", move |b| {
+        b.iter(|| encode_with_fcs(&bytes, SpecialChars::default(), Fcs::Fcs16))
+    });
+}
 
 criterion_group!(
     benches,
     bench_encode_megabyte,
     bench_decode_megabyte,
     bench_encode_special_chars_megabyte,
-    bench_decode_special_chars_2_megabytes
+    bench_decode_special_chars_2_megabytes,
+    bench_decode_slice_megabyte,
+    bench_encode_into_megabyte,
+    bench_encode_mixed_megabyte,
+    bench_decode_mixed_megabyte,
+    bench_fcs16_megabyte,
+    bench_fcs32_megabyte,
+    bench_encode_fcs16_megabyte
 );
 criterion_main!(benches);
 
@@ -0,0 +1,297 @@
+//! # ax25
+//! AX.25 address and control fields, for packet radio frames.
+//!
+//! An AX.25 frame starts with the destination and source [`Callsign`], up to 8 digipeaters,
+//! then a modulo 8 [`Control`] field and, for I and UI frames, a protocol identifier.  Frames
+//! arrive from a TNC inside KISS data frames, see [`kiss`](crate::kiss), or with flags and an
+//! FCS from an HDLC modem.
+//!
+//! ### Parse a UI frame
+//! ```rust
+//! use hdlc::ax25::{Callsign, Frame};
+//!
+//! let source: Callsign = "N0CALL-7".parse().unwrap();
+//! let bytes = Frame::ui("APRS".parse().unwrap(), source, b"!hello").to_bytes();
+//!
+//! let frame = Frame::parse(&bytes).unwrap();
+//!
+//! assert_eq!(frame.source.to_string(), "N0CALL-7");
+//! assert_eq!(frame.destination.call(), "APRS");
+//! assert_eq!(frame.pid, Some(0xF0));
+//! assert_eq!(frame.info, b"!hello");
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::frame::{Control, Unnumbered};
+use crate::HDLCError;
+
+/// Bytes per address
+const ADDRESS_LEN: usize = 7;
+/// Most digipeaters a frame can go through
+const MAX_DIGIPEATERS: usize = 8;
+/// Protocol identifier for no layer 3
+pub const PID_NO_LAYER3: u8 = 0xF0;
+
+/// Station callsign with its secondary station identifier.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Callsign {
+    // Upper case letters and digits, padded with spaces
+    call: [u8; 6],
+    ssid: u8,
+}
+
+impl Callsign {
+    /// Callsign of up to 6 letters and digits, and an SSID of 0 to 15.  Letters are upper cased.
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MalformedMessage**: The callsign is empty, too long or holds other
+    ///   characters, or the SSID is over 15.
+    pub fn new(call: &str, ssid: u8) -> Result<Callsign, HDLCError> {
+        let malformed = HDLCError::MalformedMessage {
+            protocol: "AX.25 callsign",
+        };
+        if call.is_empty() || call.len() > 6 || ssid > 15 {
+            return Err(malformed);
+        }
+        let mut padded = [b' '; 6];
+        for (slot, c) in padded.iter_mut().zip(call.bytes()) {
+            if !c.is_ascii_alphanumeric() {
+                return Err(malformed);
+            }
+            *slot = c.to_ascii_uppercase();
+        }
+
+        Ok(Callsign { call: padded, ssid })
+    }
+
+    /// The callsign without padding
+    pub fn call(&self) -> &str {
+        // Only ASCII ever gets in
+        std::str::from_utf8(&self.call)
+            .unwrap_or_default()
+            .trim_end()
+    }
+
+    /// Secondary station identifier, 0 to 15
+    pub fn ssid(&self) -> u8 {
+        self.ssid
+    }
+
+    /// Decodes one address, and returns the C/H bit and the extension bit with it
+    fn parse(field: &[u8]) -> Result<(Callsign, bool, bool), HDLCError> {
+        let mut call = [b' '; 6];
+        for (slot, &byte) in call.iter_mut().zip(field) {
+            let c = byte >> 1;
+            if byte & 0x01 != 0 || !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == b' ') {
+                return Err(HDLCError::InvalidAddress { byte });
+            }
+            *slot = c;
+        }
+        let last = field[6];
+
+        Ok((
+            Callsign {
+                call,
+                ssid: (last >> 1) & 0x0F,
+            },
+            last & 0x80 != 0,
+            last & 0x01 != 0,
+        ))
+    }
+
+    /// Encodes one address with its C/H bit and extension bit
+    fn write(&self, flag: bool, last: bool, output: &mut Vec<u8>) {
+        output.extend(self.call.iter().map(|c| c << 1));
+        output.push((u8::from(flag) << 7) | 0x60 | (self.ssid << 1) | u8::from(last));
+    }
+}
+
+impl fmt::Display for Callsign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.call())?;
+        if self.ssid != 0 {
+            write!(f, "-{}", self.ssid)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Callsign {
+    type Err = HDLCError;
+
+    /// Parses `N0CALL` or `N0CALL-7`
+    fn from_str(text: &str) -> Result<Callsign, HDLCError> {
+        match text.split_once('-') {
+            Some((call, ssid)) => {
+                let ssid = ssid.parse().map_err(|_| HDLCError::MalformedMessage {
+                    protocol: "AX.25 callsign",
+                })?;
+                Callsign::new(call, ssid)
+            }
+            None => Callsign::new(text, 0),
+        }
+    }
+}
+
+/// Digipeater in the path of a frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Digipeater {
+    /// Station that repeats the frame
+    pub callsign: Callsign,
+    /// The H bit, the station has already repeated the frame
+    pub repeated: bool,
+}
+
+/// Command/response marking from the C bits of the destination and source.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CommandResponse {
+    /// AX.25 2.x command, destination C bit set
+    #[default]
+    Command,
+    /// AX.25 2.x response, source C bit set
+    Response,
+    /// AX.25 1.x frame, both C bits equal
+    Legacy,
+}
+
+/// A decoded AX.25 frame split into its fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// Station the frame is for
+    pub destination: Callsign,
+    /// Station that sent the frame
+    pub source: Callsign,
+    /// Path through repeaters, in order
+    pub digipeaters: Vec<Digipeater>,
+    /// Command or response
+    pub kind: CommandResponse,
+    /// Modulo 8 control field
+    pub control: Control,
+    /// Protocol identifier, present on I and UI frames
+    pub pid: Option<u8>,
+    /// Information field
+    pub info: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// UI command frame without layer 3, the frame APRS and most telemetry use
+    pub fn ui(destination: Callsign, source: Callsign, info: &'a [u8]) -> Frame<'a> {
+        Frame {
+            destination,
+            source,
+            digipeaters: Vec::new(),
+            kind: CommandResponse::Command,
+            control: Control::Unnumbered {
+                kind: Unnumbered::Ui,
+                pf: false,
+            },
+            pid: Some(PID_NO_LAYER3),
+            info,
+        }
+    }
+
+    /// Splits a decoded frame, without its FCS, into its fields
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingControl**: The frame ends inside the address or control field.
+    /// * **HDLCError::InvalidAddress**: A callsign byte is not a letter, digit or space, or
+    ///   the address field ends after the destination or runs past 8 digipeaters.
+    /// * **HDLCError::MalformedMessage**: An I or UI frame has no protocol identifier.
+    pub fn parse(frame: &'a [u8]) -> Result<Frame<'a>, HDLCError> {
+        let mut addresses = Vec::with_capacity(2);
+        let mut rest = frame;
+        loop {
+            if rest.len() < ADDRESS_LEN {
+                return Err(HDLCError::MissingControl);
+            }
+            let (field, tail) = rest.split_at(ADDRESS_LEN);
+            let (callsign, flag, last) = Callsign::parse(field)?;
+            addresses.push((callsign, flag));
+            rest = tail;
+
+            if last {
+                break;
+            }
+            if addresses.len() == MAX_DIGIPEATERS + 2 {
+                return Err(HDLCError::InvalidAddress { byte: field[6] });
+            }
+        }
+        // The source is never missing, the destination cannot end the field
+        if addresses.len() < 2 {
+            return Err(HDLCError::InvalidAddress { byte: frame[6] });
+        }
+
+        let (&control, mut info) = rest.split_first().ok_or(HDLCError::MissingControl)?;
+        let control = Control::from_byte(control);
+        let pid = match control {
+            Control::Information { .. }
+            | Control::Unnumbered {
+                kind: Unnumbered::Ui,
+                ..
+            } => {
+                let (&pid, tail) = info
+                    .split_first()
+                    .ok_or(HDLCError::MalformedMessage { protocol: "AX.25" })?;
+                info = tail;
+                Some(pid)
+            }
+            _ => None,
+        };
+
+        let (destination, dest_c) = addresses[0];
+        let (source, source_c) = addresses[1];
+        let kind = match (dest_c, source_c) {
+            (true, false) => CommandResponse::Command,
+            (false, true) => CommandResponse::Response,
+            _ => CommandResponse::Legacy,
+        };
+
+        Ok(Frame {
+            destination,
+            source,
+            digipeaters: addresses[2..]
+                .iter()
+                .map(|&(callsign, repeated)| Digipeater { callsign, repeated })
+                .collect(),
+            kind,
+            control,
+            pid,
+            info,
+        })
+    }
+
+    /// Produces the frame bytes, ready for KISS or an FCS and [`encode`](crate::encode).  Only
+    /// the first 8 digipeaters are written.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let digipeaters = &self.digipeaters[..self.digipeaters.len().min(MAX_DIGIPEATERS)];
+        let mut output =
+            Vec::with_capacity((digipeaters.len() + 2) * ADDRESS_LEN + 2 + self.info.len());
+
+        let (dest_c, source_c) = match self.kind {
+            CommandResponse::Command => (true, false),
+            CommandResponse::Response => (false, true),
+            CommandResponse::Legacy => (false, false),
+        };
+        self.destination.write(dest_c, false, &mut output);
+        self.source
+            .write(source_c, digipeaters.is_empty(), &mut output);
+        for (i, digipeater) in digipeaters.iter().enumerate() {
+            let last = i + 1 == digipeaters.len();
+            digipeater
+                .callsign
+                .write(digipeater.repeated, last, &mut output);
+        }
+        output.push(self.control.to_byte());
+        if let Some(pid) = self.pid {
+            output.push(pid);
+        }
+        output.extend_from_slice(self.info);
+
+        output
+    }
+}
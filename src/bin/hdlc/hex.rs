@@ -0,0 +1,48 @@
+//! Hex dumps in and out of the command line tool
+
+use std::fmt::Write;
+
+/// Parses hex bytes such as `7E 01 7D 5E`, `0x7e,0x01` or `7e:01`.  Separators are optional
+/// between bytes, so `7E017D5E` is accepted too.
+pub fn parse(text: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+
+    for token in text.split(|c: char| c.is_whitespace() || c == ',' || c == ':') {
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        // A bare prefix is as incomplete as an odd digit
+        if digits.len() % 2 != 0 || (digits.is_empty() && !token.is_empty()) {
+            return Err(format!("odd number of hex digits in `{}`", token));
+        }
+        for pair in digits.as_bytes().chunks(2) {
+            let pair = std::str::from_utf8(pair).map_err(|_| format!("bad hex `{}`", token))?;
+            let byte = u8::from_str_radix(pair, 16).map_err(|_| format!("bad hex `{}`", token))?;
+            output.push(byte);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Parses one byte given on the command line, with or without a `0x` prefix
+pub fn parse_byte(text: &str) -> Result<u8, String> {
+    match parse(text)?.as_slice() {
+        [byte] => Ok(*byte),
+        _ => Err(format!("`{}` is not a single hex byte", text)),
+    }
+}
+
+/// Formats bytes as upper case hex separated by spaces
+pub fn format(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len() * 3);
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+            output.push(' ');
+        }
+        let _ = write!(output, "{:02X}", byte);
+    }
+
+    output
+}
@@ -0,0 +1,337 @@
+//! # hdlc
+//! Command line tool to encode, decode and inspect HDLC frames.
+//!
+//! Input is hex text by default, `7E 01 7D 5E 7E`, `0x7e,0x01` and `7E017D5E` all work, or raw
+//! bytes with `--binary`.  It is read from the file given, or from stdin.  `hdlc monitor` reads
+//! a serial port instead and prints frames as they arrive.
+
+mod hex;
+mod monitor;
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use hdlc::decoder::{decode_frames, Frames};
+use hdlc::frame::Frame;
+use hdlc::{DecodeOptions, Fcs, HDLCError, OpeningFend, SpecialChars};
+
+#[derive(Parser)]
+#[command(
+    name = "hdlc",
+    version,
+    about = "Encode, decode and inspect HDLC frames"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encode the input as one frame, or every line of hex input as its own frame
+    Encode {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        framing: Framing,
+        /// Write raw bytes instead of hex
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Decode every frame in a capture
+    Decode {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        framing: Framing,
+        /// Write the raw frames back to back instead of one hex line per frame
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Print the address, control and information fields of every frame in a capture
+    Inspect {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        framing: Framing,
+    },
+    /// Print the FCS of the input
+    Fcs {
+        #[command(flatten)]
+        input: Input,
+        /// Frame Check Sequence
+        #[arg(long, value_enum, default_value_t = FcsKind::Fcs16)]
+        fcs: FcsKind,
+    },
+    /// Decode and print frames live from a serial port, pty or pipe
+    Monitor(monitor::Monitor),
+}
+
+#[derive(Args)]
+struct Input {
+    /// Input file, stdin when omitted or `-`
+    file: Option<PathBuf>,
+    /// Read raw bytes instead of hex
+    #[arg(long)]
+    binary: bool,
+}
+
+impl Input {
+    fn read(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        match &self.file {
+            Some(path) if path.as_os_str() != "-" => {
+                bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+            _ => {
+                io::stdin()
+                    .read_to_end(&mut bytes)
+                    .map_err(|e| format!("stdin: {}", e))?;
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Input as a byte stream
+    fn bytes(&self) -> Result<Vec<u8>, String> {
+        let bytes = self.read()?;
+        if self.binary {
+            return Ok(bytes);
+        }
+        hex::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// Input as payloads, one per line of hex or the whole binary input
+    fn payloads(&self) -> Result<Vec<Vec<u8>>, String> {
+        let bytes = self.read()?;
+        if self.binary {
+            return Ok(vec![bytes]);
+        }
+        String::from_utf8_lossy(&bytes)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(hex::parse)
+            .collect()
+    }
+}
+
+#[derive(Args)]
+struct Framing {
+    /// Frame boundary
+    #[arg(long, value_name = "HEX", value_parser = hex::parse_byte, default_value = "7E")]
+    fend: u8,
+    /// Frame escape
+    #[arg(long, value_name = "HEX", value_parser = hex::parse_byte, default_value = "7D")]
+    fesc: u8,
+    /// Escaped frame boundary
+    #[arg(long, value_name = "HEX", value_parser = hex::parse_byte, default_value = "5E")]
+    tfend: u8,
+    /// Escaped frame escape
+    #[arg(long, value_name = "HEX", value_parser = hex::parse_byte, default_value = "5D")]
+    tfesc: u8,
+    /// Frame Check Sequence at the end of each frame
+    #[arg(long, value_enum, default_value_t = FcsKind::None)]
+    fcs: FcsKind,
+}
+
+impl Framing {
+    fn chars(&self) -> Result<SpecialChars, HDLCError> {
+        // Catches duplicate special characters before any frame is touched
+        SpecialChars::new(self.fend, self.fesc, self.tfend, self.tfesc)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum FcsKind {
+    /// No FCS
+    None,
+    /// FCS-16, CRC-16/X-25
+    #[value(name = "16")]
+    Fcs16,
+    /// FCS-32, CRC-32/ISO-HDLC
+    #[value(name = "32")]
+    Fcs32,
+}
+
+impl FcsKind {
+    fn fcs(self) -> Option<Fcs<'static>> {
+        match self {
+            FcsKind::None => None,
+            FcsKind::Fcs16 => Some(Fcs::Fcs16),
+            FcsKind::Fcs32 => Some(Fcs::Fcs32),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Encode {
+            input,
+            framing,
+            raw,
+        } => encode(&input, &framing, raw),
+        Command::Decode {
+            input,
+            framing,
+            raw,
+        } => decode(&input, &framing, raw),
+        Command::Inspect { input, framing } => inspect(&input, &framing),
+        Command::Fcs { input, fcs } => checksum(&input, fcs),
+        Command::Monitor(args) => monitor::run(&args),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        // Some frames were bad, they were reported along the way
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("hdlc: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Writes a frame to stdout as raw bytes or as a hex line
+fn emit(out: &mut impl Write, bytes: &[u8], raw: bool) -> Result<(), String> {
+    let result = if raw {
+        out.write_all(bytes)
+    } else {
+        writeln!(out, "{}", hex::format(bytes))
+    };
+
+    result.map_err(|e| format!("stdout: {}", e))
+}
+
+fn encode(input: &Input, framing: &Framing, raw: bool) -> Result<bool, String> {
+    let chars = framing.chars().map_err(|e| e.to_string())?;
+    let mut out = io::stdout().lock();
+
+    for payload in input.payloads()? {
+        let frame = match framing.fcs.fcs() {
+            Some(fcs) => hdlc::encode_with_fcs(&payload, chars, fcs),
+            None => hdlc::encode(&payload, chars).map_err(|e| e.to_string())?,
+        };
+        emit(&mut out, &frame, raw)?;
+    }
+
+    Ok(true)
+}
+
+/// Every frame in a capture, which may start part way through a frame
+fn frames(capture: &[u8], chars: SpecialChars) -> Frames<'_> {
+    decode_frames(capture, chars).with_options(DecodeOptions {
+        opening_fend: OpeningFend::Hunt,
+        ..DecodeOptions::default()
+    })
+}
+
+fn decode(input: &Input, framing: &Framing, raw: bool) -> Result<bool, String> {
+    let chars = framing.chars().map_err(|e| e.to_string())?;
+    let capture = input.bytes()?;
+    let mut out = io::stdout().lock();
+    let mut clean = true;
+
+    for (index, frame) in frames(&capture, chars).enumerate() {
+        let frame = match (frame, framing.fcs.fcs()) {
+            (Ok(frame), Some(fcs)) => fcs.verify(&frame).map(<[u8]>::to_vec),
+            (Ok(frame), None) => Ok(frame),
+            (Err(corrupt), _) => Err(corrupt.error),
+        };
+        match frame {
+            Ok(frame) => emit(&mut out, &frame, raw)?,
+            Err(e) => {
+                eprintln!("frame {}: {}", index, e);
+                clean = false;
+            }
+        }
+    }
+
+    Ok(clean)
+}
+
+fn inspect(input: &Input, framing: &Framing) -> Result<bool, String> {
+    let chars = framing.chars().map_err(|e| e.to_string())?;
+    let capture = input.bytes()?;
+    let mut out = io::stdout().lock();
+    let mut clean = true;
+
+    for (index, frame) in frames(&capture, chars).enumerate() {
+        let (line, ok) = match frame {
+            Ok(frame) => describe(&frame, framing.fcs.fcs()),
+            Err(corrupt) => (
+                format!("{}  raw {}", corrupt.error, hex::format(corrupt.raw)),
+                false,
+            ),
+        };
+        clean &= ok;
+        writeln!(out, "#{:<4} {}", index, line).map_err(|e| format!("stdout: {}", e))?;
+    }
+
+    Ok(clean)
+}
+
+/// One line summary of a decoded frame, and whether it is sound
+fn describe(raw: &[u8], fcs: Option<Fcs>) -> (String, bool) {
+    let split = raw.len().saturating_sub(fcs.map_or(0, |fcs| fcs.size()));
+    let (data, received) = raw.split_at(split);
+
+    let frame = match Frame::parse(data) {
+        Ok(frame) => frame,
+        Err(e) => return (format!("{}  raw {}", e, hex::format(raw)), false),
+    };
+
+    let (status, ok) = match fcs {
+        Some(fcs) => match fcs.verify(raw) {
+            Ok(_) => (format!("FCS {} ok  ", hex::format(received)), true),
+            Err(_) => {
+                let expected = fcs.compute(data);
+                let status = format!(
+                    "FCS {} bad, expected {}  ",
+                    hex::format(received),
+                    hex::format(&expected)
+                );
+                (status, false)
+            }
+        },
+        None => (String::new(), true),
+    };
+    let info = match frame.info {
+        [] => "no info".to_string(),
+        info => format!("info {}", hex::format(info)),
+    };
+    let line = format!(
+        "addr {:#04X}  {:<22}  {}{}",
+        frame.address,
+        frame.control.to_string(),
+        status,
+        info
+    );
+
+    (line, ok)
+}
+
+fn checksum(input: &Input, fcs: FcsKind) -> Result<bool, String> {
+    let data = input.bytes()?;
+    let fcs = fcs.fcs().ok_or("choose an FCS with --fcs 16 or --fcs 32")?;
+    let wire = fcs.compute(&data);
+
+    // Sent least significant byte first, so the value reads backwards
+    let value = wire
+        .iter()
+        .rev()
+        .fold(0u32, |acc, &b| (acc << 8) | u32::from(b));
+    println!(
+        "{:#0width$X}  wire {}",
+        value,
+        hex::format(&wire),
+        width = wire.len() * 2 + 2
+    );
+
+    Ok(true)
+}
@@ -0,0 +1,148 @@
+//! Live decoding of a serial port, pty or pipe
+
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::{Args, ValueEnum};
+use hdlc::decoder::{FrameDecoder, FrameEvent};
+use hdlc::{DecodeOptions, OpeningFend};
+
+use crate::{describe, Framing};
+
+/// Largest raw frame the monitor holds
+const BUF_LEN: usize = 8192;
+
+/// How long a read on a quiet serial port waits before trying again
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Args)]
+pub struct Monitor {
+    /// Serial device, pty, named pipe or capture file, `-` for stdin
+    device: PathBuf,
+    /// Baud rate of a serial device
+    #[arg(long, default_value_t = 115_200)]
+    baud: u32,
+    /// Time printed in front of each frame
+    #[arg(long, value_enum, default_value_t = Timestamps::Utc)]
+    timestamps: Timestamps,
+    /// Longest frame accepted, in bytes
+    #[arg(long, value_name = "BYTES")]
+    max_frame_len: Option<usize>,
+    #[command(flatten)]
+    framing: Framing,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Timestamps {
+    /// Wall clock time of day, UTC
+    Utc,
+    /// Seconds since the monitor started
+    Relative,
+    /// No timestamps
+    None,
+}
+
+/// Decodes frames as they arrive and prints one annotated line per frame, until the source
+/// ends
+pub fn run(args: &Monitor) -> Result<bool, String> {
+    let chars = args.framing.chars().map_err(|e| e.to_string())?;
+    let fcs = args.framing.fcs.fcs();
+    let options = DecodeOptions {
+        max_frame_len: args.max_frame_len,
+        // The line is joined part way through a frame
+        opening_fend: OpeningFend::Hunt,
+        ..DecodeOptions::default()
+    };
+    let mut decoder: FrameDecoder<BUF_LEN> = FrameDecoder::new(chars).with_options(options);
+    let mut source = open(args)?;
+    let mut out = io::stdout().lock();
+    let start = Instant::now();
+    let mut index = 0;
+    let mut clean = true;
+    let mut buf = [0u8; 1024];
+
+    loop {
+        let len = match source.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            // Serial ports time out while the line is quiet
+            Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::Interrupted) => continue,
+            Err(e) => return Err(format!("{}: {}", args.device.display(), e)),
+        };
+
+        for &byte in &buf[..len] {
+            let line = match decoder.feed(byte) {
+                None => continue,
+                Some(FrameEvent::Frame(frame)) => {
+                    let (line, ok) = describe(frame, fcs);
+                    clean &= ok;
+                    line
+                }
+                Some(FrameEvent::Abort) => "aborted by the sender".to_string(),
+                Some(FrameEvent::Overflow) => {
+                    clean = false;
+                    format!("overflow, frame longer than {} bytes", BUF_LEN)
+                }
+                Some(FrameEvent::Error(e)) => {
+                    clean = false;
+                    e.to_string()
+                }
+            };
+            let stamp = timestamp(args.timestamps, start);
+            writeln!(out, "{}#{:<4} {}", stamp, index, line)
+                .and_then(|()| out.flush())
+                .map_err(|e| format!("stdout: {}", e))?;
+            index += 1;
+        }
+    }
+
+    Ok(clean)
+}
+
+/// Opens the device as a serial port, or as a plain file for pipes and captures
+fn open(args: &Monitor) -> Result<Box<dyn Read>, String> {
+    let device = &args.device;
+    if device.as_os_str() == "-" {
+        return Ok(Box::new(io::stdin()));
+    }
+    if fs::metadata(device).is_ok_and(|meta| meta.is_file()) {
+        let file = File::open(device).map_err(|e| format!("{}: {}", device.display(), e))?;
+        return Ok(Box::new(file));
+    }
+
+    let path = device.to_string_lossy();
+    match serialport::new(path.as_ref(), args.baud)
+        .timeout(READ_TIMEOUT)
+        .open()
+    {
+        Ok(port) => Ok(Box::new(port)),
+        // Named pipes are no terminals, read them like a file
+        Err(e) => match File::open(device) {
+            Ok(file) if !file.is_terminal() => Ok(Box::new(file)),
+            _ => Err(format!("{}: {}", device.display(), e)),
+        },
+    }
+}
+
+/// Prefix for a line printed now
+fn timestamp(kind: Timestamps, start: Instant) -> String {
+    match kind {
+        Timestamps::Utc => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            let secs = now.as_secs() % 86_400;
+            format!(
+                "{:02}:{:02}:{:02}.{:03} ",
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60,
+                now.subsec_millis()
+            )
+        }
+        Timestamps::Relative => format!("+{:.6} ", start.elapsed().as_secs_f64()),
+        Timestamps::None => String::new(),
+    }
+}
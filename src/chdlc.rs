@@ -0,0 +1,344 @@
+//! # chdlc
+//! Cisco HDLC framing, as used on WAN serial links between routers.
+//!
+//! A cHDLC frame is the usual HDLC frame with a fixed layout: the address is `0x0F` for unicast
+//! or `0x8F` for broadcast, the control field is always `0x00` and a two byte EtherType says
+//! what the payload is.  Encode and decode the frames with the functions of the crate root, the
+//! FCS is normally [`Fcs::Fcs16`](crate::Fcs::Fcs16).
+//!
+//! Line state is kept with SLARP keepalives, see [`Slarp`] and [`KeepaliveTracker`].
+//!
+//! ### Build and parse a frame
+//! ```rust
+//! use hdlc::chdlc::{Frame, Protocol, Slarp};
+//!
+//! let bytes = Slarp::Keepalive { sequence: 7, seen: 6 }.to_frame();
+//! let frame = Frame::parse(&bytes).unwrap();
+//!
+//! assert_eq!(frame.protocol, Protocol::Slarp);
+//! assert_eq!(
+//!     Slarp::parse(frame.payload).unwrap(),
+//!     Slarp::Keepalive { sequence: 7, seen: 6 }
+//! );
+//! ```
+
+use std::net::Ipv4Addr;
+
+use crate::HDLCError;
+
+/// Address of a frame sent to the peer
+pub const UNICAST: u8 = 0x0F;
+/// Address of a broadcast frame, also used for SLARP
+pub const BROADCAST: u8 = 0x8F;
+/// The only control field cHDLC uses
+pub const CONTROL: u8 = 0x00;
+
+/// SLARP message codes
+const SLARP_REQUEST: u32 = 0;
+const SLARP_REPLY: u32 = 1;
+const SLARP_KEEPALIVE: u32 = 2;
+/// Length of a SLARP message as sent, reliability and uptime included
+const SLARP_LEN: usize = 18;
+/// Shortest SLARP message accepted, without the uptime
+const SLARP_MIN_LEN: usize = 14;
+
+/// EtherType in the protocol field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Protocol {
+    /// IPv4, `0x0800`
+    Ipv4,
+    /// IPv6, `0x86DD`
+    Ipv6,
+    /// Cisco Discovery Protocol, `0x2000`
+    Cdp,
+    /// Serial Line Address Resolution Protocol, `0x8035`
+    Slarp,
+    /// Any other EtherType
+    Other(u16),
+}
+
+impl Protocol {
+    /// Protocol for an EtherType
+    pub fn from_u16(value: u16) -> Protocol {
+        match value {
+            0x0800 => Protocol::Ipv4,
+            0x86DD => Protocol::Ipv6,
+            0x2000 => Protocol::Cdp,
+            0x8035 => Protocol::Slarp,
+            other => Protocol::Other(other),
+        }
+    }
+
+    /// EtherType of the protocol
+    pub fn to_u16(&self) -> u16 {
+        match *self {
+            Protocol::Ipv4 => 0x0800,
+            Protocol::Ipv6 => 0x86DD,
+            Protocol::Cdp => 0x2000,
+            Protocol::Slarp => 0x8035,
+            Protocol::Other(value) => value,
+        }
+    }
+}
+
+/// A decoded cHDLC frame split into its fields.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// [`UNICAST`] or [`BROADCAST`]
+    pub address: u8,
+    /// What the payload carries
+    pub protocol: Protocol,
+    /// The packet, without the cHDLC header
+    pub payload: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Unicast frame carrying `payload`
+    pub fn new(protocol: Protocol, payload: &'a [u8]) -> Frame<'a> {
+        Frame {
+            address: UNICAST,
+            protocol,
+            payload,
+        }
+    }
+
+    /// Splits a decoded frame, without its FCS, into its fields
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingProtocol**: The frame is shorter than the four byte header.
+    /// * **HDLCError::InvalidAddress**: The address is neither unicast nor broadcast.
+    /// * **HDLCError::InvalidControl**: The control field is not `0x00`.
+    pub fn parse(frame: &'a [u8]) -> Result<Frame<'a>, HDLCError> {
+        let (address, control, protocol, payload) = match frame {
+            [address, control, hi, lo, payload @ ..] => {
+                (*address, *control, u16::from_be_bytes([*hi, *lo]), payload)
+            }
+            _ => return Err(HDLCError::MissingProtocol),
+        };
+        if address != UNICAST && address != BROADCAST {
+            return Err(HDLCError::InvalidAddress { byte: address });
+        }
+        if control != CONTROL {
+            return Err(HDLCError::InvalidControl { byte: control });
+        }
+
+        Ok(Frame {
+            address,
+            protocol: Protocol::from_u16(protocol),
+            payload,
+        })
+    }
+
+    /// Produces the frame bytes, ready for an FCS and [`encode`](crate::encode)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(self.payload.len() + 4);
+        output.push(self.address);
+        output.push(CONTROL);
+        output.extend_from_slice(&self.protocol.to_u16().to_be_bytes());
+        output.extend_from_slice(self.payload);
+
+        output
+    }
+}
+
+/// Serial Line Address Resolution Protocol message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Slarp {
+    /// Asks the peer for its address
+    Request,
+    /// Address and mask of the sender's interface
+    Reply {
+        /// Interface address
+        address: Ipv4Addr,
+        /// Interface netmask
+        mask: Ipv4Addr,
+    },
+    /// Periodic line check
+    Keepalive {
+        /// Sequence number of this keepalive
+        sequence: u32,
+        /// Last sequence number received from the peer
+        seen: u32,
+    },
+}
+
+impl Slarp {
+    /// Decodes the payload of a SLARP frame
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MalformedMessage**: The payload is too short or has an unknown code.
+    pub fn parse(payload: &[u8]) -> Result<Slarp, HDLCError> {
+        let malformed = HDLCError::MalformedMessage { protocol: "SLARP" };
+        if payload.len() < SLARP_MIN_LEN {
+            return Err(malformed);
+        }
+        let word = |at: usize| {
+            u32::from_be_bytes([
+                payload[at],
+                payload[at + 1],
+                payload[at + 2],
+                payload[at + 3],
+            ])
+        };
+
+        match word(0) {
+            SLARP_REQUEST => Ok(Slarp::Request),
+            SLARP_REPLY => Ok(Slarp::Reply {
+                address: Ipv4Addr::from(word(4)),
+                mask: Ipv4Addr::from(word(8)),
+            }),
+            SLARP_KEEPALIVE => Ok(Slarp::Keepalive {
+                sequence: word(4),
+                seen: word(8),
+            }),
+            _ => Err(malformed),
+        }
+    }
+
+    /// Produces the SLARP payload.  The reliability field is `0xFFFF` and the uptime is 0.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (code, first, second) = match *self {
+            Slarp::Request => (SLARP_REQUEST, 0, 0),
+            Slarp::Reply { address, mask } => (SLARP_REPLY, u32::from(address), u32::from(mask)),
+            Slarp::Keepalive { sequence, seen } => (SLARP_KEEPALIVE, sequence, seen),
+        };
+
+        let mut output = Vec::with_capacity(SLARP_LEN);
+        output.extend_from_slice(&code.to_be_bytes());
+        output.extend_from_slice(&first.to_be_bytes());
+        output.extend_from_slice(&second.to_be_bytes());
+        output.extend_from_slice(&[0xFF, 0xFF]);
+        output.extend_from_slice(&[0; 4]);
+
+        output
+    }
+
+    /// Produces a whole broadcast cHDLC frame carrying the message, ready for an FCS
+    pub fn to_frame(&self) -> Vec<u8> {
+        let payload = self.to_bytes();
+
+        Frame {
+            address: BROADCAST,
+            protocol: Protocol::Slarp,
+            payload: &payload,
+        }
+        .to_bytes()
+    }
+}
+
+/// State of the line as seen through keepalives.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum LineState {
+    /// No recent keepalive from the peer acknowledged ours
+    #[default]
+    Down,
+    /// The peer acknowledges our keepalives
+    Up,
+    /// Our own keepalives come back, the line is looped
+    Looped,
+}
+
+/// Keeps track of SLARP keepalives and the line protocol state.
+///
+/// Call [`tick`](KeepaliveTracker::tick) every keepalive period, 10 seconds on Cisco routers,
+/// and send the keepalive it returns.  Hand every keepalive received to
+/// [`receive`](KeepaliveTracker::receive).  The line goes down after `max_missed` periods in a
+/// row without an acknowledgement.
+///
+/// ```rust
+/// use hdlc::chdlc::{KeepaliveTracker, LineState, Slarp};
+///
+/// let mut tracker = KeepaliveTracker::new(3);
+///
+/// let sent = tracker.tick();
+/// tracker.receive(&Slarp::Keepalive { sequence: 1, seen: 1 });
+///
+/// assert_eq!(sent, Slarp::Keepalive { sequence: 1, seen: 0 });
+/// assert_eq!(tracker.state(), LineState::Up);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeepaliveTracker {
+    max_missed: u32,
+    // Sequence number of our last keepalive, 0 before the first
+    sent: u32,
+    // Last sequence number heard from the peer
+    received: u32,
+    // The peer has seen our last keepalive
+    acked: bool,
+    missed: u32,
+    state: LineState,
+}
+
+impl Default for KeepaliveTracker {
+    /// Tracker that drops the line after 3 missed keepalives
+    fn default() -> KeepaliveTracker {
+        KeepaliveTracker::new(3)
+    }
+}
+
+impl KeepaliveTracker {
+    /// Creates a tracker for a line that is down until the peer answers
+    pub fn new(max_missed: u32) -> KeepaliveTracker {
+        KeepaliveTracker {
+            max_missed,
+            sent: 0,
+            received: 0,
+            acked: false,
+            missed: 0,
+            state: LineState::Down,
+        }
+    }
+
+    /// Starts a keepalive period and returns the keepalive to send
+    pub fn tick(&mut self) -> Slarp {
+        if self.sent != 0 && !self.acked {
+            self.missed = self.missed.saturating_add(1);
+            if self.missed >= self.max_missed {
+                self.state = LineState::Down;
+            }
+        }
+        // Sequence numbers skip 0, which stands for nothing seen yet
+        self.sent = match self.sent.wrapping_add(1) {
+            0 => 1,
+            next => next,
+        };
+        self.acked = false;
+
+        Slarp::Keepalive {
+            sequence: self.sent,
+            seen: self.received,
+        }
+    }
+
+    /// Takes in a SLARP message from the peer.  Anything but a keepalive is ignored.
+    pub fn receive(&mut self, message: &Slarp) {
+        let (sequence, seen) = match *message {
+            Slarp::Keepalive { sequence, seen } => (sequence, seen),
+            _ => return,
+        };
+        if self.sent != 0 && sequence == self.sent && seen == self.received {
+            self.state = LineState::Looped;
+            return;
+        }
+        self.received = sequence;
+
+        // A keepalive that crossed ours on the line still acknowledges the one before
+        if seen != 0 && (seen == self.sent || seen == self.sent.wrapping_sub(1)) {
+            self.acked = seen == self.sent;
+            self.missed = 0;
+            self.state = LineState::Up;
+        }
+    }
+
+    /// Current line protocol state
+    pub fn state(&self) -> LineState {
+        self.state
+    }
+
+    /// Keepalive periods in a row without an acknowledgement
+    pub fn missed(&self) -> u32 {
+        self.missed
+    }
+}
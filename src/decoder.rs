@@ -0,0 +1,571 @@
+//! # decoder
+//! Incremental, byte at a time decoding for interrupt handlers and other places where bytes
+//! arrive one by one.
+//!
+//! [`FrameDecoder`] is a small state machine over a fixed `N` byte buffer.  It never allocates,
+//! so it can be fed straight from a UART RX interrupt.  It follows the same `SpecialChars`
+//! escape rules as [`decode`](crate::decode).
+//!
+//! ### Feed bytes
+//! ```rust
+//! use hdlc::decoder::{FrameDecoder, FrameEvent};
+//! use hdlc::SpecialChars;
+//!
+//! let mut decoder: FrameDecoder<64> = FrameDecoder::new(SpecialChars::default());
+//! let mut frames = Vec::new();
+//!
+//! for byte in [0x7E, 0x01, 0x7D, 0x5E, 0x02, 0x7E] {
+//!     if let Some(FrameEvent::Frame(frame)) = decoder.feed(byte) {
+//!         frames.push(frame.to_vec());
+//!     }
+//! }
+//!
+//! assert_eq!(frames, vec![vec![0x01, 0x7E, 0x02]]);
+//! ```
+//!
+//! Buffers holding any number of frames are decoded with [`decode_frames`].  Both decoders
+//! resynchronise on the next `fend` after a bad frame, and an [`ErrorPolicy`] picks whether bad
+//! frames are reported or silently dropped.
+//!
+//! ### Decode a noisy capture
+//! ```rust
+//! use hdlc::decoder::{decode_frames, ErrorPolicy};
+//! use hdlc::SpecialChars;
+//!
+//! let capture = [0x7E, 0x01, 0x7E, 0x02, 0x7D, 0x00, 0x7E, 0x03, 0x7E];
+//!
+//! let frames: Vec<_> = decode_frames(&capture, SpecialChars::default())
+//!     .with_policy(ErrorPolicy::Discard)
+//!     .collect();
+//!
+//! assert_eq!(frames, vec![Ok(vec![0x01]), Ok(vec![0x03])]);
+//! ```
+
+#[cfg(feature = "std")]
+use crate::unstuff_to_vec;
+use crate::{HDLCError, SpecialChars};
+#[cfg(feature = "std")]
+use memchr::{memchr, memchr_iter};
+
+/// Limits on the frames accepted by the decoders.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct DecodeOptions {
+    /// Longest decoded frame in bytes, FCS included (N1).  `None`, the default, sets no limit
+    /// other than the buffer of the decoder.
+    pub max_frame_len: Option<usize>,
+    /// Shortest decoded frame in bytes, FCS included, 0 by default.  Address, control and FCS
+    /// make 4 bytes for basic HDLC with FCS-16.
+    pub min_frame_len: usize,
+    /// What happens to frames shorter than `min_frame_len`
+    pub runts: RuntPolicy,
+    /// Whether the first frame needs its opening `fend`
+    pub opening_fend: OpeningFend,
+    /// Whether the last frame needs its closing `fend`
+    pub closing_fend: ClosingFend,
+    /// Lets [`decode`](crate::decode) and [`decode_slice`](crate::decode_slice) return the
+    /// first frame when the start of another follows its closing `fend`, `false` by default.
+    /// The other decoders always carry on with the next frame.
+    pub trailing_partial: bool,
+}
+
+/// Whether the decoders need a `fend` before the first frame.
+///
+/// SLIP (RFC 1055) senders often leave out the opening END, so the first frame starts with the
+/// input.  Later frames are opened by the closing `fend` of the one before either way.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum OpeningFend {
+    /// The input must start with a `fend`, [`HDLCError::MissingFirstFend`] otherwise.  The
+    /// stream decoders report the bytes before the first `fend` with that error, then carry on
+    /// from it.
+    #[default]
+    Required,
+    /// Bytes before the first `fend` are garbage and dropped without a word.  Use it for
+    /// streams joined part way through a frame.
+    Hunt,
+    /// The start of the input counts as a `fend`
+    Optional,
+}
+
+/// Whether the decoders need a `fend` after the last frame.
+///
+/// Only decoders that see the end of the input, [`decode`](crate::decode),
+/// [`decode_slice`](crate::decode_slice) and [`decode_frames`], can close a frame there.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ClosingFend {
+    /// A frame without its closing `fend` fails with [`HDLCError::MissingFinalFend`], or is
+    /// left in [`Frames::remainder`]
+    #[default]
+    Required,
+    /// The end of the input counts as a `fend`
+    Optional,
+}
+
+/// What the decoders do with runt frames, shorter than [`DecodeOptions::min_frame_len`].
+///
+/// Empty frames between back to back `fend` are always fill.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum RuntPolicy {
+    /// Fail the frame with [`HDLCError::RuntFrame`]
+    #[default]
+    Report,
+    /// Take the runt as inter-frame fill, its closing `fend` opens the next frame.  A frame
+    /// decoder that only holds a runt still reports it.
+    Skip,
+}
+
+/// What the decoders do with frames that fail to decode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Hand bad frames back with their error, then carry on with the next frame.
+    #[default]
+    Report,
+    /// Drop bad frames without a word and carry on with the next frame.
+    Discard,
+}
+
+/// Where the decoder is within the byte stream.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DecoderState {
+    /// Waiting for a `fend` to synchronise on.  Every other byte is discarded.
+    Hunting,
+    /// Inside a frame, collecting bytes.
+    InFrame,
+    /// The last byte was a `fesc`, the next one must be a `tfend` or `tfesc`.
+    Escape,
+}
+
+/// Something the decoder has to report after a byte was fed.
+#[derive(Debug, PartialEq)]
+pub enum FrameEvent<'a> {
+    /// A complete frame was received.  The slice is valid until the next call to `feed`.
+    Frame(&'a [u8]),
+    /// The sender aborted the frame with `fesc` followed by `fend`.
+    Abort,
+    /// The frame did not fit in the buffer.  The decoder is hunting for the next `fend`.
+    /// Not reported under [`ErrorPolicy::Discard`].
+    Overflow,
+    /// The frame failed to decode.  The decoder is hunting for the next `fend`.  Not reported
+    /// under [`ErrorPolicy::Discard`].
+    Error(HDLCError),
+}
+
+/// Byte at a time HDLC decoder with a fixed `N` byte frame buffer.
+///
+/// A closing `fend` also opens the next frame, and back to back `fend` are treated as
+/// inter-frame fill, so no empty frames are reported.  Error offsets count every byte fed since
+/// the decoder was created, and frames are numbered from 0 in the order they ended.
+#[derive(Debug, Clone)]
+pub struct FrameDecoder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    state: DecoderState,
+    s_chars: SpecialChars,
+    policy: ErrorPolicy,
+    options: DecodeOptions,
+    // Bytes fed and frames ended so far, for error positions
+    position: usize,
+    frame: usize,
+    // A FEND was seen, or the bytes before the first one were reported
+    synced: bool,
+}
+
+impl<const N: usize> FrameDecoder<N> {
+    /// Creates a new FrameDecoder that is hunting for the first `fend`
+    pub const fn new(s_chars: SpecialChars) -> FrameDecoder<N> {
+        FrameDecoder {
+            buf: [0; N],
+            len: 0,
+            state: DecoderState::Hunting,
+            s_chars,
+            policy: ErrorPolicy::Report,
+            options: DecodeOptions {
+                max_frame_len: None,
+                min_frame_len: 0,
+                runts: RuntPolicy::Report,
+                opening_fend: OpeningFend::Required,
+                closing_fend: ClosingFend::Required,
+                trailing_partial: false,
+            },
+            position: 0,
+            frame: 0,
+            synced: false,
+        }
+    }
+
+    /// Sets what happens to frames that overflow or fail to decode, `Report` by default
+    pub const fn with_policy(mut self, policy: ErrorPolicy) -> FrameDecoder<N> {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets limits on the frames accepted.  Frames longer than `max_frame_len` are reported as
+    /// [`HDLCError::FrameTooLong`] and the decoder hunts for the next `fend`.  Runts are
+    /// reported as [`HDLCError::RuntFrame`] or skipped, following `runts`.  With
+    /// [`OpeningFend::Optional`], a decoder that has not been fed yet is already in a frame.
+    pub const fn with_options(mut self, options: DecodeOptions) -> FrameDecoder<N> {
+        if self.position == 0 && matches!(options.opening_fend, OpeningFend::Optional) {
+            self.state = DecoderState::InFrame;
+        }
+        self.options = options;
+        self
+    }
+
+    /// Current state of the decoder
+    pub fn state(&self) -> DecoderState {
+        self.state
+    }
+
+    /// Drops any partial frame and goes back to hunting for a `fend`
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.state = DecoderState::Hunting;
+    }
+
+    /// Feeds one received byte into the decoder
+    ///
+    /// # Output
+    ///
+    /// * **Option<FrameEvent>**: `None` while a frame is in progress, otherwise the event
+    ///   the byte completed
+    pub fn feed(&mut self, byte: u8) -> Option<FrameEvent<'_>> {
+        let policy = self.policy;
+        match self.step(byte) {
+            Some(FrameEvent::Overflow | FrameEvent::Error(_)) if policy == ErrorPolicy::Discard => {
+                None
+            }
+            event => event,
+        }
+    }
+
+    fn step(&mut self, byte: u8) -> Option<FrameEvent<'_>> {
+        let s_chars = self.s_chars;
+        let offset = self.position;
+        self.position = self.position.wrapping_add(1);
+
+        match self.state {
+            DecoderState::Hunting => {
+                if byte == s_chars.fend {
+                    self.len = 0;
+                    self.state = DecoderState::InFrame;
+                    self.synced = true;
+                } else if !self.synced && self.options.opening_fend == OpeningFend::Required {
+                    // Only the first byte of the garbage is reported
+                    self.synced = true;
+                    return Some(FrameEvent::Error(HDLCError::MissingFirstFend {
+                        byte: Some(byte),
+                    }));
+                }
+                None
+            }
+            DecoderState::InFrame => match byte {
+                val if val == s_chars.fend => {
+                    // Back to back FENDs are fill, not empty frames
+                    if self.len == 0 {
+                        return None;
+                    }
+                    let len = self.len;
+                    self.len = 0;
+                    if len < self.options.min_frame_len {
+                        // A skipped runt is fill
+                        if self.options.runts == RuntPolicy::Skip {
+                            return None;
+                        }
+                        let error = HDLCError::RuntFrame {
+                            offset,
+                            len,
+                            min: self.options.min_frame_len,
+                            frame: self.frame,
+                        };
+                        self.end_frame();
+                        return Some(FrameEvent::Error(error));
+                    }
+                    self.end_frame();
+                    Some(FrameEvent::Frame(&self.buf[..len]))
+                }
+                val if val == s_chars.fesc => {
+                    self.state = DecoderState::Escape;
+                    None
+                }
+                _ => self.push(byte, offset),
+            },
+            DecoderState::Escape => match byte {
+                val if val == s_chars.tfend => {
+                    self.state = DecoderState::InFrame;
+                    self.push(s_chars.fend, offset)
+                }
+                val if val == s_chars.tfesc => {
+                    self.state = DecoderState::InFrame;
+                    self.push(s_chars.fesc, offset)
+                }
+                // The FEND of an abort sequence can open the next frame
+                val if val == s_chars.fend => {
+                    self.len = 0;
+                    self.state = DecoderState::InFrame;
+                    self.end_frame();
+                    Some(FrameEvent::Abort)
+                }
+                _ => {
+                    let error = HDLCError::MissingTradeChar {
+                        offset,
+                        byte: Some(byte),
+                        frame: self.frame,
+                    };
+                    self.reset();
+                    self.end_frame();
+                    Some(FrameEvent::Error(error))
+                }
+            },
+        }
+    }
+
+    fn push(&mut self, byte: u8, offset: usize) -> Option<FrameEvent<'_>> {
+        if let Some(max) = self.options.max_frame_len {
+            if self.len >= max {
+                let error = HDLCError::FrameTooLong {
+                    offset,
+                    max,
+                    frame: self.frame,
+                };
+                self.reset();
+                self.end_frame();
+                return Some(FrameEvent::Error(error));
+            }
+        }
+        match self.buf.get_mut(self.len) {
+            Some(slot) => {
+                *slot = byte;
+                self.len += 1;
+                None
+            }
+            None => {
+                self.reset();
+                self.end_frame();
+                Some(FrameEvent::Overflow)
+            }
+        }
+    }
+
+    fn end_frame(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+    }
+}
+
+/// A frame that failed to decode, handed back by [`Frames`] under [`ErrorPolicy::Report`].
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+pub struct CorruptFrame<'a> {
+    /// Why the frame failed to decode
+    pub error: HDLCError,
+    /// The frame as received, still escaped and without its `fend`
+    pub raw: &'a [u8],
+}
+
+/// Produces an iterator over every frame in `input`
+///
+/// Bytes before the first `fend` fail with [`HDLCError::MissingFirstFend`], unless
+/// [`OpeningFend::Hunt`] drops them.  A closing `fend` may also open the next frame,
+/// and runs of `fend` are inter-frame fill.  A frame that fails to decode does not stop the
+/// iterator, decoding picks up again at the next `fend`.  Errors carry their offset in `input`
+/// and the index of the frame, counting fill as no frame.  Frames cut short with the abort
+/// sequence come back as [`HDLCError::Aborted`].  A trailing frame without its closing
+/// `fend` is not yielded, see [`Frames::remainder`].  [`Frames::with_options`] can make both
+/// flags optional.
+///
+/// # Example
+/// ```rust
+/// use hdlc::decoder::decode_frames;
+/// use hdlc::{HDLCError, SpecialChars};
+///
+/// let capture = [0x7E, 0x01, 0x7E, 0x02, 0x7D, 0x00, 0x7E, 0x03, 0x7E, 0x04];
+/// let mut frames = decode_frames(&capture, SpecialChars::default());
+///
+/// assert_eq!(frames.next().unwrap(), Ok(vec![0x01]));
+/// assert_eq!(
+///     frames.next().unwrap().unwrap_err().error,
+///     HDLCError::MissingTradeChar { offset: 5, byte: Some(0x00), frame: 1 }
+/// );
+/// assert_eq!(frames.next().unwrap(), Ok(vec![0x03]));
+/// assert_eq!(frames.next(), None);
+/// assert_eq!(frames.remainder(), [0x7E, 0x04]);
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_frames(input: &[u8], s_chars: SpecialChars) -> Frames<'_> {
+    Frames {
+        rest: input,
+        input_len: input.len(),
+        frame: 0,
+        synced: false,
+        s_chars,
+        policy: ErrorPolicy::Report,
+        options: DecodeOptions::default(),
+    }
+}
+
+/// Iterator over the frames in a buffer, created by [`decode_frames`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct Frames<'a> {
+    // Starts with the opening FEND once synced, unless the first one is optional
+    rest: &'a [u8],
+    input_len: usize,
+    // Index of the next frame
+    frame: usize,
+    synced: bool,
+    s_chars: SpecialChars,
+    policy: ErrorPolicy,
+    options: DecodeOptions,
+}
+
+#[cfg(feature = "std")]
+impl<'a> Frames<'a> {
+    /// Sets what happens to frames that fail to decode, `Report` by default
+    pub fn with_policy(mut self, policy: ErrorPolicy) -> Frames<'a> {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets limits on the frames accepted.  Frames longer than `max_frame_len` fail with
+    /// [`HDLCError::FrameTooLong`], and runts fail with [`HDLCError::RuntFrame`] or are skipped,
+    /// following `runts`.  With [`OpeningFend::Optional`], the first frame starts with the input,
+    /// and with [`ClosingFend::Optional`] a trailing frame is yielded instead of left over.
+    pub fn with_options(mut self, options: DecodeOptions) -> Frames<'a> {
+        self.options = options;
+        self
+    }
+
+    /// Input not consumed yet
+    ///
+    /// Once the iterator is done, this is the trailing partial frame starting with its opening
+    /// `fend`.  Put it in front of the next chunk read from a stream to pick the frame up.
+    /// A partial frame already longer than `max_frame_len` fails with
+    /// [`HDLCError::FrameTooLong`] instead and is dropped, so the rest of it starts the next
+    /// chunk as garbage before the first `fend`.
+    pub fn remainder(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Frames<'a> {
+    // Fails the unclosed frame at the end of the input once it is over N1, so a stream that
+    // never sends its closing FEND cannot grow the remainder without limit
+    fn unclosed(&mut self, body: &'a [u8]) -> Option<Result<Vec<u8>, CorruptFrame<'a>>> {
+        let max = self.options.max_frame_len?;
+        let escapes = memchr_iter(self.s_chars.fesc, body).count();
+        if body.len() - escapes <= max {
+            return None;
+        }
+
+        let frame = self.frame;
+        let mut output = Vec::with_capacity(max);
+        let error = unstuff_to_vec(body, &mut output, self.s_chars, Some(max))
+            .err()?
+            .relocate(self.input_len - body.len(), frame);
+        // The rest of the frame, up to the next FEND, is garbage in the next chunk
+        self.rest = &[];
+        self.frame += 1;
+
+        match self.policy {
+            ErrorPolicy::Report => Some(Err(CorruptFrame { error, raw: body })),
+            ErrorPolicy::Discard => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<Vec<u8>, CorruptFrame<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let fend = self.s_chars.fend;
+
+        // Hunt for the opening FEND, bytes before it are garbage
+        if !self.synced && self.options.opening_fend != OpeningFend::Optional {
+            let pos = memchr(fend, self.rest).unwrap_or(self.rest.len());
+            let (garbage, rest) = self.rest.split_at(pos);
+            self.rest = rest;
+            self.synced = true;
+
+            let reported = self.options.opening_fend == OpeningFend::Required
+                && self.policy == ErrorPolicy::Report;
+            if let (Some(&byte), true) = (garbage.first(), reported) {
+                return Some(Err(CorruptFrame {
+                    error: HDLCError::MissingFirstFend { byte: Some(byte) },
+                    raw: garbage,
+                }));
+            }
+        }
+
+        loop {
+            // Only the first frame can be without its opening FEND
+            let body = match self.rest.split_first() {
+                Some((&first, body)) if first == fend => body,
+                _ => self.rest,
+            };
+            let end = match memchr(fend, body) {
+                Some(end) => end,
+                // The end of the input closes the last frame when the options allow it
+                None if self.options.closing_fend == ClosingFend::Optional => body.len(),
+                None => return self.unclosed(body),
+            };
+            let raw = &body[..end];
+            // The closing FEND opens the next frame
+            self.rest = &body[end..];
+
+            // Back to back FENDs are fill, not empty frames
+            if raw.is_empty() {
+                if self.rest.is_empty() {
+                    return None;
+                }
+                continue;
+            }
+
+            let frame = self.frame;
+            let capacity = self
+                .options
+                .max_frame_len
+                .map_or(raw.len(), |max| max.min(raw.len()));
+            let mut output = Vec::with_capacity(capacity);
+            let mut result =
+                unstuff_to_vec(raw, &mut output, self.s_chars, self.options.max_frame_len)
+                    .map_err(|error| match error {
+                        // Without a closing FEND nothing follows the FESC
+                        HDLCError::Aborted { offset, .. } if self.rest.is_empty() => {
+                            HDLCError::MissingTradeChar {
+                                offset: offset + 1,
+                                byte: None,
+                                frame,
+                            }
+                        }
+                        error => error,
+                    })
+                    .map_err(|error| error.relocate(self.input_len - body.len(), frame));
+
+            let min = self.options.min_frame_len;
+            if result.is_ok() && output.len() < min {
+                // A skipped runt is fill
+                if self.options.runts == RuntPolicy::Skip {
+                    continue;
+                }
+                result = Err(HDLCError::RuntFrame {
+                    offset: self.input_len - self.rest.len(),
+                    len: output.len(),
+                    min,
+                    frame,
+                });
+            }
+            self.frame += 1;
+
+            match result {
+                Ok(()) => return Some(Ok(output)),
+                Err(error) if self.policy == ErrorPolicy::Report => {
+                    return Some(Err(CorruptFrame { error, raw }))
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::iter::FusedIterator for Frames<'_> {}
@@ -0,0 +1,194 @@
+//! # encoder
+//! Stateful encoding for transmitters that keep the line busy between frames.
+//!
+//! [`Encoder`] remembers what it sent last, so consecutive frames can share a single flag, and
+//! it generates idle fill between frames.  [`EncodeOptions`] picks the number of opening flags,
+//! whether flags are shared and the longest frame allowed.
+//!
+//! ### Shared flags and idle fill
+//! ```rust
+//! use hdlc::encoder::{EncodeOptions, Encoder, IdleFill};
+//! use hdlc::SpecialChars;
+//!
+//! let options = EncodeOptions {
+//!     shared_flags: true,
+//!     ..EncodeOptions::default()
+//! };
+//! let mut encoder = Encoder::new(SpecialChars::default()).with_options(options);
+//! let mut wire = Vec::new();
+//!
+//! encoder.encode_to_vec(&[0x01], &mut wire).unwrap();
+//! encoder.encode_to_vec(&[0x02], &mut wire).unwrap();
+//! encoder.idle(IdleFill::Mark, 2, &mut wire);
+//! encoder.encode_to_vec(&[0x03], &mut wire).unwrap();
+//!
+//! assert_eq!(
+//!     wire,
+//!     [0x7E, 0x01, 0x7E, 0x02, 0x7E, 0xFF, 0xFF, 0x7E, 0x03, 0x7E]
+//! );
+//! ```
+//!
+//! The decoders only skip flags, so mark idle between two flags reaches the receiver as a
+//! frame of `0xFF`.  Drop it with [`IdleFill::is_idle`] on links where no payload is all
+//! `0xFF`.
+
+use crate::{stuff_to_vec, HDLCError, SpecialChars};
+
+/// Byte sent in mark idle, all 1s
+const MARK: u8 = 0xFF;
+
+/// What goes on the line while there is no frame to send.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum IdleFill {
+    /// Continuous `fend`.  The last one can open the next frame when flags are shared.
+    #[default]
+    Flags,
+    /// Mark idle, all 1s (`0xFF`).  The next frame gets its opening flags again.  The decoders
+    /// hand mark idle back as a frame, since a lone `0xFF` can be a real frame such as a KISS
+    /// return, so the receiver has to discard it.
+    Mark,
+}
+
+impl IdleFill {
+    /// Whether a decoded frame is only this idle fill and should be discarded
+    ///
+    /// Mark idle is any non-empty frame of all `0xFF`, so do not use this on links where such
+    /// a payload is valid, a KISS return for one.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hdlc::encoder::IdleFill;
+    ///
+    /// assert!(IdleFill::Mark.is_idle(&[0xFF, 0xFF]));
+    /// assert!(!IdleFill::Mark.is_idle(&[0xFF, 0x03]));
+    /// assert!(!IdleFill::Mark.is_idle(&[]));
+    /// ```
+    pub fn is_idle(&self, frame: &[u8]) -> bool {
+        match self {
+            // Runs of flags never come out of the decoders
+            IdleFill::Flags => frame.is_empty(),
+            IdleFill::Mark => !frame.is_empty() && frame.iter().all(|&b| b == MARK),
+        }
+    }
+}
+
+/// How frames are delimited by an [`Encoder`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Number of `fend` sent before each frame, 1 by default.  Some async peers expect 2, and
+    /// 0 only works when the line is already sending flags.
+    pub opening_flags: usize,
+    /// Lets the closing `fend` of a frame also open the next one, `false` by default.
+    pub shared_flags: bool,
+    /// Longest payload in bytes (N1).  The `Encoder` adds no FCS, so one appended by the
+    /// caller counts towards it.  `None`, the default, sets no limit.
+    pub max_frame_len: Option<usize>,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> EncodeOptions {
+        EncodeOptions {
+            opening_flags: 1,
+            shared_flags: false,
+            max_frame_len: None,
+        }
+    }
+}
+
+/// Frame encoder that keeps track of the flags already on the line.
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    s_chars: SpecialChars,
+    options: EncodeOptions,
+    // The last byte sent was a FEND the next frame can open with
+    flag_sent: bool,
+}
+
+impl Encoder {
+    /// Creates a new Encoder with the default [`EncodeOptions`]
+    pub fn new(s_chars: SpecialChars) -> Encoder {
+        Encoder {
+            s_chars,
+            options: EncodeOptions::default(),
+            flag_sent: false,
+        }
+    }
+
+    /// Sets how frames are delimited
+    pub fn with_options(mut self, options: EncodeOptions) -> Encoder {
+        self.options = options;
+        self
+    }
+
+    /// Options in use
+    pub fn options(&self) -> EncodeOptions {
+        self.options
+    }
+
+    /// Escapes `data` and pushes it with its flags onto the end of `output`
+    ///
+    /// # Output
+    ///
+    /// * **Result<usize>**: Number of bytes pushed onto `output`
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::PayloadTooLong**: `data` is longer than `max_frame_len`.  Nothing is
+    ///   pushed.
+    pub fn encode_to_vec(&mut self, data: &[u8], output: &mut Vec<u8>) -> Result<usize, HDLCError> {
+        if let Some(max) = self.options.max_frame_len {
+            if data.len() > max {
+                return Err(HDLCError::PayloadTooLong {
+                    len: data.len(),
+                    max,
+                });
+            }
+        }
+
+        let s_chars = self.s_chars;
+        let start = output.len();
+
+        // The closing FEND of the last frame opens this one
+        let opening = if self.options.shared_flags && self.flag_sent {
+            0
+        } else {
+            self.options.opening_flags
+        };
+        output.reserve(data.len() + opening + 1);
+
+        output.resize(start + opening, s_chars.fend);
+        stuff_to_vec(data, output, s_chars);
+        output.push(s_chars.fend);
+        self.flag_sent = true;
+
+        Ok(output.len() - start)
+    }
+
+    /// Produces the escaped frame with its flags
+    ///
+    /// Errors are the same as [`Encoder::encode_to_vec`].
+    pub fn encode(&mut self, data: &[u8]) -> Result<Vec<u8>, HDLCError> {
+        let mut output = Vec::with_capacity(data.len() * 2);
+        self.encode_to_vec(data, &mut output)?;
+
+        Ok(output)
+    }
+
+    /// Pushes `count` bytes of idle fill onto the end of `output`
+    pub fn idle(&mut self, fill: IdleFill, count: usize, output: &mut Vec<u8>) {
+        let byte = match fill {
+            IdleFill::Flags => self.s_chars.fend,
+            IdleFill::Mark => MARK,
+        };
+        output.resize(output.len() + count, byte);
+
+        if count > 0 {
+            self.flag_sent = fill == IdleFill::Flags;
+        }
+    }
+
+    /// Forgets the flags on the line, so the next frame gets its opening flags
+    pub fn reset(&mut self) {
+        self.flag_sent = false;
+    }
+}
@@ -0,0 +1,623 @@
+//! # fcs
+//! Frame Check Sequence calculators for the ISO/IEC 13239 FCS-16 and FCS-32.
+//!
+//! Both calculators are streaming: feed data with `update` as it is produced, then call
+//! `finish`.  The engine behind them is picked at compile time with a cargo feature:
+//!
+//! * **default**: one 256 entry table, a lookup per byte.
+//! * **crc-slice8**: slicing-by-8, eight tables and eight bytes per step.  Fastest on CPUs with
+//!   a big data cache.  Wins if several engine features are enabled.
+//! * **crc-bitwise**: no tables at all, eight shifts per byte.  Smallest, for tiny MCUs.
+//!
+//! Links that use another CRC can describe it with [`CrcParams`], build its table driven
+//! [`Crc`] once and pass it as [`Fcs::Custom`] to the encoders and decoders.  Custom CRCs use
+//! that table regardless of the engine feature.
+//!
+//! ### Streaming FCS-16
+//! ```rust
+//! use hdlc::fcs::Fcs16;
+//!
+//! let mut fcs = Fcs16::new();
+//! fcs.update(b"1234");
+//! fcs.update(b"56789");
+//!
+//! assert_eq!(fcs.finish(), 0x906E);
+//! assert_eq!(Fcs16::checksum(b"123456789"), 0x906E);
+//! ```
+//!
+//! ### Custom CRC
+//! ```rust
+//! use hdlc::fcs::{Crc, CrcParams};
+//! use hdlc::{encode_with_fcs, Fcs, SpecialChars};
+//!
+//! let mut crc = Crc::new(CrcParams::CRC16_MODBUS).unwrap();
+//! let frame = encode_with_fcs(b"123456789", SpecialChars::default(), Fcs::Custom(&crc));
+//! crc.update(b"123456789");
+//!
+//! assert_eq!(crc.finish(), 0x4B37);
+//! assert_eq!(frame[10..12], [0x37, 0x4B]);
+//! ```
+
+use crate::HDLCError;
+
+/// Reflected FCS-16 polynomial, x^16 + x^12 + x^5 + 1
+const POLY16: u16 = 0x8408;
+/// Reflected FCS-32 polynomial, the IEEE 802.3 one
+const POLY32: u32 = 0xEDB8_8320;
+
+/// Which Frame Check Sequence protects a frame.
+///
+/// The FCS is sent least significant byte first after the data, and is stuffed like any other
+/// byte.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fcs<'a> {
+    /// 16 bit FCS (CRC-16/X-25), used by PPP and most HDLC links
+    Fcs16,
+    /// 32 bit FCS (CRC-32/ISO-HDLC)
+    Fcs32,
+    /// Any other CRC, run with the table of a [`Crc`] built once for every frame.  Only the
+    /// parameters of the `Crc` are used, not its running value.  Sent least significant byte
+    /// first when `refout` is set, most significant byte first otherwise.
+    Custom(&'a Crc),
+}
+
+impl Fcs<'_> {
+    /// Number of FCS bytes on the wire
+    pub fn size(&self) -> usize {
+        match self {
+            Fcs::Fcs16 => 2,
+            Fcs::Fcs32 => 4,
+            Fcs::Custom(crc) => usize::from(crc.params.width / 8),
+        }
+    }
+
+    /// FCS of `data` in the order it is sent on the wire
+    ///
+    /// # Example
+    /// ```rust
+    /// use hdlc::Fcs;
+    ///
+    /// assert_eq!(Fcs::Fcs16.compute(&[0xFF, 0x03]), [0x1C, 0xC2]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn compute(&self, data: &[u8]) -> Vec<u8> {
+        let mut state = FcsState::new(*self);
+        state.update(data);
+
+        state.finish()[..self.size()].to_vec()
+    }
+
+    /// Checks the FCS at the end of a decoded frame and returns the data before it
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingFcs**: The frame is shorter than the FCS.
+    /// * **HDLCError::FcsMismatch**: The received FCS does not match the one computed over the
+    ///   frame.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hdlc::Fcs;
+    ///
+    /// assert_eq!(Fcs::Fcs16.verify(&[0xFF, 0x03, 0x1C, 0xC2]).unwrap(), [0xFF, 0x03]);
+    /// ```
+    pub fn verify<'a>(&self, frame: &'a [u8]) -> Result<&'a [u8], HDLCError> {
+        let len = frame
+            .len()
+            .checked_sub(self.size())
+            .ok_or(HDLCError::MissingFcs)?;
+        let (data, received) = frame.split_at(len);
+
+        let mut state = FcsState::new(*self);
+        state.update(data);
+        if state.finish()[..self.size()] != *received {
+            return Err(HDLCError::FcsMismatch);
+        }
+
+        Ok(data)
+    }
+}
+
+/// Rocksoft style description of a CRC, as used by the CRC catalogues.
+///
+/// Only whole byte widths from 8 to 32 bits can be used as an FCS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CrcParams {
+    /// Width of the CRC in bits
+    pub width: u8,
+    /// Generator polynomial, normal (not reflected) form without the top bit
+    pub poly: u32,
+    /// Initial register value
+    pub init: u32,
+    /// Input bytes are processed least significant bit first
+    pub refin: bool,
+    /// Register is reflected before the final XOR
+    pub refout: bool,
+    /// Value XORed into the register at the end
+    pub xorout: u32,
+}
+
+impl CrcParams {
+    /// CRC-16/X-25, the same CRC as [`Fcs::Fcs16`]
+    pub const CRC16_X25: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x1021,
+        init: 0xFFFF,
+        refin: true,
+        refout: true,
+        xorout: 0xFFFF,
+    };
+    /// CRC-32/ISO-HDLC, the same CRC as [`Fcs::Fcs32`]
+    pub const CRC32_ISO_HDLC: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04C1_1DB7,
+        init: 0xFFFF_FFFF,
+        refin: true,
+        refout: true,
+        xorout: 0xFFFF_FFFF,
+    };
+    /// CRC-16/MODBUS
+    pub const CRC16_MODBUS: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x8005,
+        init: 0xFFFF,
+        refin: true,
+        refout: true,
+        xorout: 0x0000,
+    };
+    /// CRC-16/KERMIT, also known as CRC-16/CCITT
+    pub const CRC16_KERMIT: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x1021,
+        init: 0x0000,
+        refin: true,
+        refout: true,
+        xorout: 0x0000,
+    };
+    /// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE
+    pub const CRC16_IBM_3740: CrcParams = CrcParams {
+        width: 16,
+        poly: 0x1021,
+        init: 0xFFFF,
+        refin: false,
+        refout: false,
+        xorout: 0x0000,
+    };
+    /// CRC-32/BZIP2, the non-reflected twin of CRC-32/ISO-HDLC
+    pub const CRC32_BZIP2: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04C1_1DB7,
+        init: 0xFFFF_FFFF,
+        refin: false,
+        refout: false,
+        xorout: 0xFFFF_FFFF,
+    };
+}
+
+/// Streaming calculator for any [`CrcParams`], driven by a table built in [`Crc::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crc {
+    params: CrcParams,
+    table: [u32; 256],
+    crc: u32,
+}
+
+impl Crc {
+    /// Creates a new calculator for `params`
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::InvalidCrcParams**: The width is not 8, 16, 24 or 32 bits.
+    pub fn new(params: CrcParams) -> Result<Crc, HDLCError> {
+        if !matches!(params.width, 8 | 16 | 24 | 32) {
+            return Err(HDLCError::InvalidCrcParams);
+        }
+
+        let width = u32::from(params.width);
+        let mask = width_mask(width);
+        let mut table = [0u32; 256];
+        if params.refin {
+            // Shift right through the reflected polynomial
+            let poly = reflect(params.poly & mask, width);
+            for (n, entry) in table.iter_mut().enumerate() {
+                let mut crc = n as u32;
+                for _ in 0..8 {
+                    crc = if crc & 1 != 0 {
+                        (crc >> 1) ^ poly
+                    } else {
+                        crc >> 1
+                    };
+                }
+                *entry = crc;
+            }
+        } else {
+            // Shift left with the byte lined up under the top of the register
+            let top = 1 << (width - 1);
+            for (n, entry) in table.iter_mut().enumerate() {
+                let mut crc = (n as u32) << (width - 8);
+                for _ in 0..8 {
+                    crc = if crc & top != 0 {
+                        (crc << 1) ^ params.poly
+                    } else {
+                        crc << 1
+                    };
+                }
+                *entry = crc & mask;
+            }
+        }
+
+        let mut crc = Crc {
+            params,
+            table,
+            crc: 0,
+        };
+        crc.reset();
+
+        Ok(crc)
+    }
+
+    /// Parameters this calculator was built for
+    pub fn params(&self) -> CrcParams {
+        self.params
+    }
+
+    /// Adds `data` to the running CRC
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc = self.step(self.crc, data);
+    }
+
+    /// Final CRC value
+    pub fn finish(&self) -> u32 {
+        self.output(self.crc)
+    }
+
+    /// Restarts the calculation from the initial value
+    pub fn reset(&mut self) {
+        self.crc = self.initial();
+    }
+
+    /// Register value before any data
+    fn initial(&self) -> u32 {
+        let width = u32::from(self.params.width);
+        let init = self.params.init & width_mask(width);
+        if self.params.refin {
+            reflect(init, width)
+        } else {
+            init
+        }
+    }
+
+    /// Register value `crc` after `data`
+    fn step(&self, mut crc: u32, data: &[u8]) -> u32 {
+        let width = u32::from(self.params.width);
+        if self.params.refin {
+            for &byte in data {
+                crc = (crc >> 8) ^ self.table[usize::from((crc as u8) ^ byte)];
+            }
+        } else {
+            let mask = width_mask(width);
+            for &byte in data {
+                let index = ((crc >> (width - 8)) as u8) ^ byte;
+                crc = ((crc << 8) & mask) ^ self.table[usize::from(index)];
+            }
+        }
+        crc
+    }
+
+    /// CRC value of the register `crc`
+    fn output(&self, crc: u32) -> u32 {
+        let width = u32::from(self.params.width);
+        // The register is kept reflected when the input is, undo that unless refout asks for it
+        let crc = if self.params.refin == self.params.refout {
+            crc
+        } else {
+            reflect(crc, width)
+        };
+
+        (crc ^ self.params.xorout) & width_mask(width)
+    }
+}
+
+/// Mask of the low `width` bits
+fn width_mask(width: u32) -> u32 {
+    u32::MAX >> (32 - width)
+}
+
+/// Reverses the low `width` bits of `value`
+fn reflect(value: u32, width: u32) -> u32 {
+    value.reverse_bits() >> (32 - width)
+}
+
+/// Streaming FCS-16 (CRC-16/X-25) calculator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Fcs16 {
+    crc: u16,
+}
+
+impl Default for Fcs16 {
+    fn default() -> Fcs16 {
+        Fcs16::new()
+    }
+}
+
+impl Fcs16 {
+    /// Creates a new calculator with the initial value `0xFFFF`
+    pub const fn new() -> Fcs16 {
+        Fcs16 { crc: 0xFFFF }
+    }
+
+    /// Adds `data` to the running FCS
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc = engine::update16(self.crc, data);
+    }
+
+    /// Final FCS value, as sent on the wire least significant byte first
+    pub fn finish(&self) -> u16 {
+        !self.crc
+    }
+
+    /// FCS-16 of `data` in one go
+    pub fn checksum(data: &[u8]) -> u16 {
+        let mut fcs = Fcs16::new();
+        fcs.update(data);
+        fcs.finish()
+    }
+}
+
+/// Streaming FCS-32 (CRC-32/ISO-HDLC) calculator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Fcs32 {
+    crc: u32,
+}
+
+impl Default for Fcs32 {
+    fn default() -> Fcs32 {
+        Fcs32::new()
+    }
+}
+
+impl Fcs32 {
+    /// Creates a new calculator with the initial value `0xFFFFFFFF`
+    pub const fn new() -> Fcs32 {
+        Fcs32 { crc: 0xFFFF_FFFF }
+    }
+
+    /// Adds `data` to the running FCS
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc = engine::update32(self.crc, data);
+    }
+
+    /// Final FCS value, as sent on the wire least significant byte first
+    pub fn finish(&self) -> u32 {
+        !self.crc
+    }
+
+    /// FCS-32 of `data` in one go
+    pub fn checksum(data: &[u8]) -> u32 {
+        let mut fcs = Fcs32::new();
+        fcs.update(data);
+        fcs.finish()
+    }
+}
+
+/// Running calculator for whichever [`Fcs`] a frame uses.
+#[derive(Debug, Clone)]
+pub(crate) enum FcsState<'a> {
+    Fcs16(Fcs16),
+    Fcs32(Fcs32),
+    // The shared calculator and the register value of this frame
+    Custom(&'a Crc, u32),
+}
+
+impl<'a> FcsState<'a> {
+    pub(crate) fn new(fcs: Fcs<'a>) -> FcsState<'a> {
+        match fcs {
+            Fcs::Fcs16 => FcsState::Fcs16(Fcs16::new()),
+            Fcs::Fcs32 => FcsState::Fcs32(Fcs32::new()),
+            Fcs::Custom(crc) => FcsState::Custom(crc, crc.initial()),
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            FcsState::Fcs16(fcs) => fcs.update(data),
+            FcsState::Fcs32(fcs) => fcs.update(data),
+            FcsState::Custom(crc, value) => *value = crc.step(*value, data),
+        }
+    }
+
+    /// FCS bytes in wire order, only the first [`Fcs::size`] of them are used
+    pub(crate) fn finish(&self) -> [u8; 4] {
+        match self {
+            FcsState::Fcs16(fcs) => {
+                let [lo, hi] = fcs.finish().to_le_bytes();
+                [lo, hi, 0, 0]
+            }
+            FcsState::Fcs32(fcs) => fcs.finish().to_le_bytes(),
+            FcsState::Custom(crc, value) => {
+                let size = usize::from(crc.params.width / 8);
+                let value = crc.output(*value);
+                let mut bytes = [0u8; 4];
+                if crc.params.refout {
+                    bytes[..size].copy_from_slice(&value.to_le_bytes()[..size]);
+                } else {
+                    bytes[..size].copy_from_slice(&value.to_be_bytes()[4 - size..]);
+                }
+                bytes
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "crc-bitwise", not(feature = "crc-slice8")))]
+mod engine {
+    use super::{POLY16, POLY32};
+
+    pub(super) fn update16(mut crc: u16, data: &[u8]) -> u16 {
+        for &byte in data {
+            crc ^= u16::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY16
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        crc
+    }
+
+    pub(super) fn update32(mut crc: u32, data: &[u8]) -> u32 {
+        for &byte in data {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY32
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        crc
+    }
+}
+
+#[cfg(any(feature = "crc-slice8", not(feature = "crc-bitwise")))]
+mod tables {
+    use super::{POLY16, POLY32};
+
+    /// `N` slicing tables for the reflected FCS-16, table 0 is the plain bytewise table
+    pub(super) const fn slices16<const N: usize>() -> [[u16; 256]; N] {
+        let mut tables = [[0u16; 256]; N];
+        let mut n = 0;
+        while n < 256 {
+            let mut crc = n as u16;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY16
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            tables[0][n] = crc;
+            n += 1;
+        }
+
+        let mut k = 1;
+        while k < N {
+            let mut n = 0;
+            while n < 256 {
+                let prev = tables[k - 1][n];
+                tables[k][n] = (prev >> 8) ^ tables[0][(prev & 0xFF) as usize];
+                n += 1;
+            }
+            k += 1;
+        }
+        tables
+    }
+
+    /// `N` slicing tables for the reflected FCS-32, table 0 is the plain bytewise table
+    pub(super) const fn slices32<const N: usize>() -> [[u32; 256]; N] {
+        let mut tables = [[0u32; 256]; N];
+        let mut n = 0;
+        while n < 256 {
+            let mut crc = n as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY32
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            tables[0][n] = crc;
+            n += 1;
+        }
+
+        let mut k = 1;
+        while k < N {
+            let mut n = 0;
+            while n < 256 {
+                let prev = tables[k - 1][n];
+                tables[k][n] = (prev >> 8) ^ tables[0][(prev & 0xFF) as usize];
+                n += 1;
+            }
+            k += 1;
+        }
+        tables
+    }
+}
+
+#[cfg(not(any(feature = "crc-bitwise", feature = "crc-slice8")))]
+mod engine {
+    use super::tables::{slices16, slices32};
+
+    static TABLE16: [u16; 256] = slices16::<1>()[0];
+    static TABLE32: [u32; 256] = slices32::<1>()[0];
+
+    pub(super) fn update16(mut crc: u16, data: &[u8]) -> u16 {
+        for &byte in data {
+            crc = (crc >> 8) ^ TABLE16[usize::from((crc as u8) ^ byte)];
+        }
+        crc
+    }
+
+    pub(super) fn update32(mut crc: u32, data: &[u8]) -> u32 {
+        for &byte in data {
+            crc = (crc >> 8) ^ TABLE32[usize::from((crc as u8) ^ byte)];
+        }
+        crc
+    }
+}
+
+#[cfg(feature = "crc-slice8")]
+mod engine {
+    use super::tables::{slices16, slices32};
+
+    static TABLES16: [[u16; 256]; 8] = slices16::<8>();
+    static TABLES32: [[u32; 256]; 8] = slices32::<8>();
+
+    pub(super) fn update16(mut crc: u16, data: &[u8]) -> u16 {
+        let t = &TABLES16;
+        let mut chunks = data.chunks_exact(8);
+        for c in &mut chunks {
+            let lo = crc ^ u16::from_le_bytes([c[0], c[1]]);
+            crc = t[7][usize::from(lo as u8)]
+                ^ t[6][usize::from(lo >> 8)]
+                ^ t[5][usize::from(c[2])]
+                ^ t[4][usize::from(c[3])]
+                ^ t[3][usize::from(c[4])]
+                ^ t[2][usize::from(c[5])]
+                ^ t[1][usize::from(c[6])]
+                ^ t[0][usize::from(c[7])];
+        }
+        for &byte in chunks.remainder() {
+            crc = (crc >> 8) ^ t[0][usize::from((crc as u8) ^ byte)];
+        }
+        crc
+    }
+
+    pub(super) fn update32(mut crc: u32, data: &[u8]) -> u32 {
+        let t = &TABLES32;
+        let mut chunks = data.chunks_exact(8);
+        for c in &mut chunks {
+            let lo = crc ^ u32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+            let hi = u32::from_le_bytes([c[4], c[5], c[6], c[7]]);
+            crc = t[7][(lo & 0xFF) as usize]
+                ^ t[6][((lo >> 8) & 0xFF) as usize]
+                ^ t[5][((lo >> 16) & 0xFF) as usize]
+                ^ t[4][(lo >> 24) as usize]
+                ^ t[3][(hi & 0xFF) as usize]
+                ^ t[2][((hi >> 8) & 0xFF) as usize]
+                ^ t[1][((hi >> 16) & 0xFF) as usize]
+                ^ t[0][(hi >> 24) as usize];
+        }
+        for &byte in chunks.remainder() {
+            crc = (crc >> 8) ^ t[0][usize::from((crc as u8) ^ byte)];
+        }
+        crc
+    }
+}
@@ -0,0 +1,549 @@
+//! # frame
+//! Address, control and information fields of a decoded frame.
+//!
+//! The decoders hand back the bytes between the flags.  [`Frame::parse`] splits them into the
+//! one byte address, the modulo 8 control field and the information field, for links that use
+//! the basic HDLC frame format.  Strip the FCS first, see [`Fcs::verify`](crate::Fcs::verify).
+//! Links in extended mode use the modulo 128 [`ExtendedControl`] instead.
+//!
+//! ### Parse a frame
+//! ```rust
+//! use hdlc::frame::{Control, Frame, Supervisory};
+//!
+//! let frame = Frame::parse(&[0x03, 0xB1]).unwrap();
+//!
+//! assert_eq!(frame.address, 0x03);
+//! assert_eq!(
+//!     frame.control,
+//!     Control::Supervisory { kind: Supervisory::Rr, nr: 5, pf: true }
+//! );
+//! assert_eq!(frame.control.to_string(), "RR N(R)=5 P/F");
+//! ```
+//!
+//! ### Build a frame
+//! ```rust
+//! use hdlc::frame::Frame;
+//! use hdlc::{Fcs, SpecialChars};
+//!
+//! let wire = Frame::command(0x03)
+//!     .i_frame(2, 5, true)
+//!     .info(&[0x7E])
+//!     .encode(SpecialChars::default(), Fcs::Fcs16)
+//!     .unwrap();
+//!
+//! assert_eq!(wire, [0x7E, 0x03, 0xB4, 0x7D, 0x5E, 0x5F, 0xEE, 0x7E]);
+//! ```
+
+use core::fmt;
+
+use crate::HDLCError;
+#[cfg(feature = "std")]
+use crate::{encode_with_fcs, Fcs, SpecialChars};
+
+/// Poll/final bit of the control field
+const PF: u8 = 0x10;
+
+/// Supervisory frame functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Supervisory {
+    /// Receive ready
+    Rr,
+    /// Receive not ready
+    Rnr,
+    /// Reject
+    Rej,
+    /// Selective reject
+    Srej,
+}
+
+impl Supervisory {
+    /// Mnemonic used in traces
+    pub fn name(&self) -> &'static str {
+        match self {
+            Supervisory::Rr => "RR",
+            Supervisory::Rnr => "RNR",
+            Supervisory::Rej => "REJ",
+            Supervisory::Srej => "SREJ",
+        }
+    }
+
+    /// Function in bits 2-3 of the first control byte
+    fn from_bits(byte: u8) -> Supervisory {
+        match (byte >> 2) & 0x03 {
+            0 => Supervisory::Rr,
+            1 => Supervisory::Rnr,
+            2 => Supervisory::Rej,
+            _ => Supervisory::Srej,
+        }
+    }
+
+    /// First control byte without N(R) and P/F
+    fn to_bits(self) -> u8 {
+        let s = match self {
+            Supervisory::Rr => 0,
+            Supervisory::Rnr => 1,
+            Supervisory::Rej => 2,
+            Supervisory::Srej => 3,
+        };
+
+        (s << 2) | 0x01
+    }
+}
+
+/// Unnumbered frame functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Unnumbered {
+    /// Unnumbered information
+    Ui,
+    /// Set asynchronous balanced mode
+    Sabm,
+    /// Set asynchronous balanced mode extended
+    Sabme,
+    /// Set normal response mode
+    Snrm,
+    /// Disconnect
+    Disc,
+    /// Unnumbered acknowledgment
+    Ua,
+    /// Disconnected mode
+    Dm,
+    /// Frame reject
+    Frmr,
+    /// Exchange identification
+    Xid,
+    /// Test
+    Test,
+    /// Any other function, the control byte without its P/F bit
+    Other(u8),
+}
+
+impl Unnumbered {
+    /// Decodes the control byte of a U frame, P/F bit ignored
+    pub fn from_byte(byte: u8) -> Unnumbered {
+        match byte & !PF {
+            0x03 => Unnumbered::Ui,
+            0x2F => Unnumbered::Sabm,
+            0x6F => Unnumbered::Sabme,
+            0x83 => Unnumbered::Snrm,
+            0x43 => Unnumbered::Disc,
+            0x63 => Unnumbered::Ua,
+            0x0F => Unnumbered::Dm,
+            0x87 => Unnumbered::Frmr,
+            0xAF => Unnumbered::Xid,
+            0xE3 => Unnumbered::Test,
+            other => Unnumbered::Other(other),
+        }
+    }
+
+    /// Control byte of the function, P/F bit clear
+    pub fn to_byte(&self) -> u8 {
+        match self {
+            Unnumbered::Ui => 0x03,
+            Unnumbered::Sabm => 0x2F,
+            Unnumbered::Sabme => 0x6F,
+            Unnumbered::Snrm => 0x83,
+            Unnumbered::Disc => 0x43,
+            Unnumbered::Ua => 0x63,
+            Unnumbered::Dm => 0x0F,
+            Unnumbered::Frmr => 0x87,
+            Unnumbered::Xid => 0xAF,
+            Unnumbered::Test => 0xE3,
+            Unnumbered::Other(byte) => byte & !PF,
+        }
+    }
+
+    /// Mnemonic used in traces
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unnumbered::Ui => "UI",
+            Unnumbered::Sabm => "SABM",
+            Unnumbered::Sabme => "SABME",
+            Unnumbered::Snrm => "SNRM",
+            Unnumbered::Disc => "DISC",
+            Unnumbered::Ua => "UA",
+            Unnumbered::Dm => "DM",
+            Unnumbered::Frmr => "FRMR",
+            Unnumbered::Xid => "XID",
+            Unnumbered::Test => "TEST",
+            Unnumbered::Other(_) => "U",
+        }
+    }
+}
+
+/// Modulo 8 control field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Control {
+    /// Information frame, carries sequenced data
+    Information {
+        /// Send sequence number N(S)
+        ns: u8,
+        /// Receive sequence number N(R)
+        nr: u8,
+        /// Poll/final bit
+        pf: bool,
+    },
+    /// Supervisory frame, flow and error control
+    Supervisory {
+        /// Supervisory function
+        kind: Supervisory,
+        /// Receive sequence number N(R)
+        nr: u8,
+        /// Poll/final bit
+        pf: bool,
+    },
+    /// Unnumbered frame, link control and unsequenced data
+    Unnumbered {
+        /// Unnumbered function
+        kind: Unnumbered,
+        /// Poll/final bit
+        pf: bool,
+    },
+}
+
+impl Control {
+    /// Decodes a modulo 8 control byte.  Every byte is a valid control field.
+    pub fn from_byte(byte: u8) -> Control {
+        let pf = byte & PF != 0;
+        let nr = byte >> 5;
+
+        if byte & 0x01 == 0 {
+            return Control::Information {
+                ns: (byte >> 1) & 0x07,
+                nr,
+                pf,
+            };
+        }
+        match byte & 0x03 {
+            0x01 => Control::Supervisory {
+                kind: Supervisory::from_bits(byte),
+                nr,
+                pf,
+            },
+            _ => Control::Unnumbered {
+                kind: Unnumbered::from_byte(byte),
+                pf,
+            },
+        }
+    }
+
+    /// Encodes the control byte.  Sequence numbers are taken modulo 8.
+    pub fn to_byte(&self) -> u8 {
+        let (byte, pf) = match *self {
+            Control::Information { ns, nr, pf } => (((nr & 0x07) << 5) | ((ns & 0x07) << 1), pf),
+            Control::Supervisory { kind, nr, pf } => (((nr & 0x07) << 5) | kind.to_bits(), pf),
+            Control::Unnumbered { kind, pf } => (kind.to_byte(), pf),
+        };
+
+        if pf {
+            byte | PF
+        } else {
+            byte
+        }
+    }
+
+    /// Poll/final bit
+    pub fn pf(&self) -> bool {
+        match *self {
+            Control::Information { pf, .. }
+            | Control::Supervisory { pf, .. }
+            | Control::Unnumbered { pf, .. } => pf,
+        }
+    }
+}
+
+impl fmt::Display for Control {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Control::Information { ns, nr, .. } => write!(f, "I N(S)={} N(R)={}", ns, nr)?,
+            Control::Supervisory { kind, nr, .. } => write!(f, "{} N(R)={}", kind.name(), nr)?,
+            Control::Unnumbered {
+                kind: Unnumbered::Other(byte),
+                ..
+            } => write!(f, "U {:#04X}", byte)?,
+            Control::Unnumbered { kind, .. } => f.write_str(kind.name())?,
+        }
+        if self.pf() {
+            f.write_str(" P/F")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Modulo 128 control field, as used by LAPD and extended mode LAPB.
+///
+/// I and S frames take two bytes, with 7 bit sequence numbers and the P/F bit in the low bit
+/// of the second byte.  U frames keep their single byte.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExtendedControl {
+    /// Information frame, carries sequenced data
+    Information {
+        /// Send sequence number N(S)
+        ns: u8,
+        /// Receive sequence number N(R)
+        nr: u8,
+        /// Poll/final bit
+        pf: bool,
+    },
+    /// Supervisory frame, flow and error control
+    Supervisory {
+        /// Supervisory function
+        kind: Supervisory,
+        /// Receive sequence number N(R)
+        nr: u8,
+        /// Poll/final bit
+        pf: bool,
+    },
+    /// Unnumbered frame, link control and unsequenced data
+    Unnumbered {
+        /// Unnumbered function
+        kind: Unnumbered,
+        /// Poll/final bit
+        pf: bool,
+    },
+}
+
+impl ExtendedControl {
+    /// Decodes the control field at the start of `field` and returns it with the bytes after it
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingControl**: `field` ends inside the control field.
+    /// * **HDLCError::InvalidControl**: The reserved bits of an S frame are set.
+    pub fn parse(field: &[u8]) -> Result<(ExtendedControl, &[u8]), HDLCError> {
+        let first = *field.first().ok_or(HDLCError::MissingControl)?;
+        if first & 0x03 == 0x03 {
+            let control = ExtendedControl::Unnumbered {
+                kind: Unnumbered::from_byte(first),
+                pf: first & PF != 0,
+            };
+            return Ok((control, &field[1..]));
+        }
+
+        let second = *field.get(1).ok_or(HDLCError::MissingControl)?;
+        let nr = second >> 1;
+        let pf = second & 0x01 != 0;
+        let control = if first & 0x01 == 0 {
+            ExtendedControl::Information {
+                ns: first >> 1,
+                nr,
+                pf,
+            }
+        } else if first & 0xF0 != 0 {
+            return Err(HDLCError::InvalidControl { byte: first });
+        } else {
+            ExtendedControl::Supervisory {
+                kind: Supervisory::from_bits(first),
+                nr,
+                pf,
+            }
+        };
+
+        Ok((control, &field[2..]))
+    }
+
+    /// Encodes the control field, one or two bytes.  Sequence numbers are taken modulo 128.
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let pf_bit = u8::from(self.pf());
+        match *self {
+            ExtendedControl::Information { ns, nr, .. } => {
+                vec![(ns & 0x7F) << 1, ((nr & 0x7F) << 1) | pf_bit]
+            }
+            ExtendedControl::Supervisory { kind, nr, .. } => {
+                vec![kind.to_bits(), ((nr & 0x7F) << 1) | pf_bit]
+            }
+            ExtendedControl::Unnumbered { kind, pf } => {
+                vec![Control::Unnumbered { kind, pf }.to_byte()]
+            }
+        }
+    }
+
+    /// Poll/final bit
+    pub fn pf(&self) -> bool {
+        match *self {
+            ExtendedControl::Information { pf, .. }
+            | ExtendedControl::Supervisory { pf, .. }
+            | ExtendedControl::Unnumbered { pf, .. } => pf,
+        }
+    }
+}
+
+impl fmt::Display for ExtendedControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Same trace text as the modulo 8 field, only the sequence numbers are wider
+        let fields = match *self {
+            ExtendedControl::Information { ns, nr, pf } => Control::Information { ns, nr, pf },
+            ExtendedControl::Supervisory { kind, nr, pf } => Control::Supervisory { kind, nr, pf },
+            ExtendedControl::Unnumbered { kind, pf } => Control::Unnumbered { kind, pf },
+        };
+
+        fields.fmt(f)
+    }
+}
+
+/// A decoded frame split into its fields.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// Address of the secondary station
+    pub address: u8,
+    /// Control field
+    pub control: Control,
+    /// Information field, empty for most S and U frames
+    pub info: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Splits a decoded frame, without its FCS, into its fields
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingControl**: The frame is shorter than its address and control
+    ///   fields.
+    pub fn parse(frame: &'a [u8]) -> Result<Frame<'a>, HDLCError> {
+        match frame {
+            [address, control, info @ ..] => Ok(Frame {
+                address: *address,
+                control: Control::from_byte(*control),
+                info,
+            }),
+            _ => Err(HDLCError::MissingControl),
+        }
+    }
+
+    /// Produces the frame bytes, ready for an FCS and [`encode`](crate::encode)
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(self.info.len() + 2);
+        output.push(self.address);
+        output.push(self.control.to_byte());
+        output.extend_from_slice(self.info);
+
+        output
+    }
+
+    /// Starts building a command frame for the station at `address`
+    pub fn command(address: u8) -> FrameBuilder<'a> {
+        FrameBuilder::new(address, true)
+    }
+
+    /// Starts building a response frame from the station at `address`
+    pub fn response(address: u8) -> FrameBuilder<'a> {
+        FrameBuilder::new(address, false)
+    }
+}
+
+/// Builds a frame field by field, created by [`Frame::command`] and [`Frame::response`].
+///
+/// The frame is a UI frame without information until its control and information fields are
+/// set.  Fields are checked when the frame is built, so a bad sequence number or a function
+/// that does not fit the command/response role never makes it onto the line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FrameBuilder<'a> {
+    address: u8,
+    command: bool,
+    control: Control,
+    info: &'a [u8],
+}
+
+impl<'a> FrameBuilder<'a> {
+    fn new(address: u8, command: bool) -> FrameBuilder<'a> {
+        FrameBuilder {
+            address,
+            command,
+            control: Control::Unnumbered {
+                kind: Unnumbered::Ui,
+                pf: false,
+            },
+            info: &[],
+        }
+    }
+
+    /// Makes an I frame with send and receive sequence numbers 0 to 7
+    pub fn i_frame(mut self, ns: u8, nr: u8, pf: bool) -> FrameBuilder<'a> {
+        self.control = Control::Information { ns, nr, pf };
+        self
+    }
+
+    /// Makes an S frame with a receive sequence number 0 to 7
+    pub fn s_frame(mut self, kind: Supervisory, nr: u8, pf: bool) -> FrameBuilder<'a> {
+        self.control = Control::Supervisory { kind, nr, pf };
+        self
+    }
+
+    /// Makes a U frame
+    pub fn u_frame(mut self, kind: Unnumbered, pf: bool) -> FrameBuilder<'a> {
+        self.control = Control::Unnumbered { kind, pf };
+        self
+    }
+
+    /// Sets the information field
+    pub fn info(mut self, info: &'a [u8]) -> FrameBuilder<'a> {
+        self.info = info;
+        self
+    }
+
+    /// Checks the fields and produces the frame
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::SequenceOutOfRange**: N(S) or N(R) is over 7.
+    /// * **HDLCError::InvalidControl**: The function does not fit the frame: a command-only U
+    ///   function in a response or the other way around, an `Unnumbered::Other` byte that is
+    ///   not a U frame, or information on an S frame or a U frame that carries none.
+    pub fn build(&self) -> Result<Frame<'a>, HDLCError> {
+        let (sequence, fits) = match self.control {
+            Control::Information { ns, nr, .. } => (ns.max(nr), true),
+            Control::Supervisory { nr, .. } => (nr, self.info.is_empty()),
+            Control::Unnumbered { kind, .. } => (0, fits_role(kind, self.command, self.info)),
+        };
+        if sequence > 7 {
+            return Err(HDLCError::SequenceOutOfRange {
+                value: sequence,
+                modulus: 8,
+            });
+        }
+        if !fits {
+            return Err(HDLCError::InvalidControl {
+                byte: self.control.to_byte(),
+            });
+        }
+
+        Ok(Frame {
+            address: self.address,
+            control: self.control,
+            info: self.info,
+        })
+    }
+
+    /// Checks the fields and produces the frame with its FCS, encoded and ready to send
+    ///
+    /// # Error
+    ///
+    /// * Every error of [`build`](FrameBuilder::build)
+    #[cfg(feature = "std")]
+    pub fn encode(&self, s_chars: SpecialChars, fcs: Fcs) -> Result<Vec<u8>, HDLCError> {
+        Ok(encode_with_fcs(&self.build()?.to_bytes(), s_chars, fcs))
+    }
+}
+
+/// Whether a U frame with `info` can be sent as a command, or as a response otherwise
+fn fits_role(kind: Unnumbered, command: bool, info: &[u8]) -> bool {
+    let role = match kind {
+        Unnumbered::Sabm | Unnumbered::Sabme | Unnumbered::Snrm | Unnumbered::Disc => command,
+        Unnumbered::Ua | Unnumbered::Dm | Unnumbered::Frmr => !command,
+        Unnumbered::Ui | Unnumbered::Xid | Unnumbered::Test => true,
+        // Anything else would build an I or S control byte
+        Unnumbered::Other(byte) => byte & 0x03 == 0x03,
+    };
+    let carries_info = matches!(
+        kind,
+        Unnumbered::Ui
+            | Unnumbered::Frmr
+            | Unnumbered::Xid
+            | Unnumbered::Test
+            | Unnumbered::Other(_)
+    );
+
+    role && (carries_info || info.is_empty())
+}
@@ -0,0 +1,568 @@
+//! # frame_relay
+//! Frame Relay (Q.922 LAPF core) frames and LMI link management.
+//!
+//! Each frame starts with a 2, 3 or 4 byte [`Address`] holding the DLCI, the congestion bits
+//! FECN and BECN, the discard eligibility bit DE and the C/R bit.  LAPF core has no control
+//! field, the rest of the frame is the payload.  Frames are encoded and decoded with the
+//! functions of the crate root and [`Fcs::Fcs16`](crate::Fcs::Fcs16).
+//!
+//! The Local Management Interface reports the status of every PVC on the link.  [`LmiMessage`]
+//! reads and writes the status enquiries and status messages of ANSI T1.617 Annex D and ITU-T
+//! Q.933 Annex A, and [`LmiPoller`] runs the user side polling.
+//!
+//! ### Parse a frame
+//! ```rust
+//! use hdlc::frame_relay::{Address, Frame};
+//!
+//! let frame = Frame::parse(&[0x18, 0x47, 0x03, 0xCC, 0x45]).unwrap();
+//!
+//! assert_eq!(frame.address.dlci, 100);
+//! assert!(frame.address.becn);
+//! assert!(frame.address.de);
+//! assert_eq!(frame.payload, [0x03, 0xCC, 0x45]);
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::HDLCError;
+
+/// Control field of LMI frames, UI
+const LMI_CONTROL: u8 = 0x03;
+/// Protocol discriminator of LMI messages
+const LMI_DISCRIMINATOR: u8 = 0x08;
+/// Dummy call reference of LMI messages
+const LMI_CALL_REFERENCE: u8 = 0x00;
+/// Locking shift to codeset 5, in front of the ANSI information elements
+const ANSI_LOCKING_SHIFT: u8 = 0x95;
+/// Message types
+const STATUS_ENQUIRY: u8 = 0x75;
+const STATUS: u8 = 0x7D;
+/// PVC status bits
+const PVC_NEW: u8 = 0x08;
+const PVC_DELETED: u8 = 0x04;
+const PVC_ACTIVE: u8 = 0x02;
+
+/// Size of an [`Address`] field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum AddressLen {
+    /// 2 bytes, 10 bit DLCI
+    #[default]
+    Two,
+    /// 3 bytes, 16 bit DLCI
+    Three,
+    /// 4 bytes, 23 bit DLCI
+    Four,
+}
+
+impl AddressLen {
+    /// Number of bytes of the address field
+    pub const fn bytes(&self) -> usize {
+        match self {
+            AddressLen::Two => 2,
+            AddressLen::Three => 3,
+            AddressLen::Four => 4,
+        }
+    }
+
+    /// Number of DLCI bits
+    pub const fn dlci_bits(&self) -> u32 {
+        match self {
+            AddressLen::Two => 10,
+            AddressLen::Three => 16,
+            AddressLen::Four => 23,
+        }
+    }
+}
+
+/// Q.922 address field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Address {
+    /// Data Link Connection Identifier
+    pub dlci: u32,
+    /// Command/response bit, passed through by the network
+    pub cr: bool,
+    /// Forward explicit congestion notification
+    pub fecn: bool,
+    /// Backward explicit congestion notification
+    pub becn: bool,
+    /// Discard eligibility
+    pub de: bool,
+    /// Size of the field
+    pub len: AddressLen,
+}
+
+impl Address {
+    /// Address for `dlci` with every bit clear, in the smallest field that holds the DLCI
+    pub const fn new(dlci: u32) -> Address {
+        let len = if dlci < 1 << 10 {
+            AddressLen::Two
+        } else if dlci < 1 << 16 {
+            AddressLen::Three
+        } else {
+            AddressLen::Four
+        };
+
+        Address {
+            dlci,
+            cr: false,
+            fecn: false,
+            becn: false,
+            de: false,
+            len,
+        }
+    }
+
+    /// Decodes the address at the start of a frame
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingControl**: The frame ends inside the address.
+    /// * **HDLCError::InvalidAddress**: The address extension bits do not end the field
+    ///   after 2 to 4 bytes, or the last byte carries DL-CORE control instead of DLCI bits.
+    pub fn parse(frame: &[u8]) -> Result<Address, HDLCError> {
+        let first = *frame.first().ok_or(HDLCError::MissingControl)?;
+        if first & 0x01 != 0 {
+            return Err(HDLCError::InvalidAddress { byte: first });
+        }
+        let end = frame
+            .iter()
+            .take(4)
+            .position(|byte| byte & 0x01 != 0)
+            .ok_or(match frame.get(3) {
+                Some(&byte) => HDLCError::InvalidAddress { byte },
+                None => HDLCError::MissingControl,
+            })?;
+        let field = &frame[..=end];
+        let second = field[1];
+
+        let high = u32::from(first >> 2);
+        let middle = u32::from(second >> 4);
+        let (dlci, len) = match *field {
+            [_, _] => ((high << 4) | middle, AddressLen::Two),
+            [_, _, last] => {
+                check_dc(last)?;
+                (
+                    (high << 10) | (middle << 6) | u32::from(last >> 2),
+                    AddressLen::Three,
+                )
+            }
+            [_, _, third, last] => {
+                check_dc(last)?;
+                let dlci = (high << 17)
+                    | (middle << 13)
+                    | (u32::from(third >> 1) << 6)
+                    | u32::from(last >> 2);
+                (dlci, AddressLen::Four)
+            }
+            _ => unreachable!("the address ends on its second to fourth byte"),
+        };
+
+        Ok(Address {
+            dlci,
+            cr: first & 0x02 != 0,
+            fecn: second & 0x08 != 0,
+            becn: second & 0x04 != 0,
+            de: second & 0x02 != 0,
+            len,
+        })
+    }
+
+    /// Encodes the address field.  The DLCI is taken modulo the size of the field.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bits = self.len.dlci_bits();
+        let dlci = self.dlci & ((1 << bits) - 1);
+        // The first two bytes carry the 10 most significant bits
+        let top = dlci >> (bits - 10);
+
+        let mut output = Vec::with_capacity(self.len.bytes());
+        output.push((((top >> 4) as u8) << 2) | (u8::from(self.cr) << 1));
+        output.push(
+            (((top & 0x0F) as u8) << 4)
+                | (u8::from(self.fecn) << 3)
+                | (u8::from(self.becn) << 2)
+                | (u8::from(self.de) << 1),
+        );
+        match self.len {
+            AddressLen::Two => {}
+            AddressLen::Three => output.push(((dlci & 0x3F) as u8) << 2),
+            AddressLen::Four => {
+                output.push((((dlci >> 6) & 0x7F) as u8) << 1);
+                output.push(((dlci & 0x3F) as u8) << 2);
+            }
+        }
+        if let Some(last) = output.last_mut() {
+            *last |= 0x01;
+        }
+
+        output
+    }
+}
+
+/// The last byte of a 3 or 4 byte address must hold DLCI bits, D/C clear
+fn check_dc(last: u8) -> Result<(), HDLCError> {
+    if last & 0x02 != 0 {
+        return Err(HDLCError::InvalidAddress { byte: last });
+    }
+
+    Ok(())
+}
+
+/// A decoded Frame Relay frame split into its address and payload.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// DLCI and congestion bits
+    pub address: Address,
+    /// Everything after the address, such as an RFC 2427 header and its packet
+    pub payload: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Splits a decoded frame, without its FCS, into its address and payload
+    ///
+    /// # Error
+    ///
+    /// Errors are the same as [`Address::parse`].
+    pub fn parse(frame: &'a [u8]) -> Result<Frame<'a>, HDLCError> {
+        let address = Address::parse(frame)?;
+
+        Ok(Frame {
+            address,
+            payload: &frame[address.len.bytes()..],
+        })
+    }
+
+    /// Produces the frame bytes, ready for an FCS and [`encode`](crate::encode)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = self.address.to_bytes();
+        output.extend_from_slice(self.payload);
+
+        output
+    }
+}
+
+/// LMI standard spoken on the link.  Both use DLCI 0.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum LmiVariant {
+    /// ANSI T1.617 Annex D
+    #[default]
+    AnsiAnnexD,
+    /// ITU-T Q.933 Annex A
+    Q933AnnexA,
+}
+
+impl LmiVariant {
+    /// Identifiers of the report type, link integrity verification and PVC status elements
+    const fn elements(&self) -> [u8; 3] {
+        match self {
+            LmiVariant::AnsiAnnexD => [0x01, 0x03, 0x07],
+            LmiVariant::Q933AnnexA => [0x51, 0x53, 0x57],
+        }
+    }
+}
+
+/// LMI message types.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LmiMessageKind {
+    /// Poll from the user side
+    StatusEnquiry,
+    /// Answer from the network side
+    Status,
+}
+
+/// What a status enquiry asks for, or a status reports.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReportType {
+    /// Link integrity and the status of every PVC
+    FullStatus,
+    /// Link integrity only, the keepalive exchange
+    LinkIntegrity,
+    /// Asynchronous status of a single PVC
+    SinglePvc,
+}
+
+impl ReportType {
+    fn from_byte(byte: u8) -> Option<ReportType> {
+        match byte {
+            0x00 => Some(ReportType::FullStatus),
+            0x01 => Some(ReportType::LinkIntegrity),
+            0x02 => Some(ReportType::SinglePvc),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            ReportType::FullStatus => 0x00,
+            ReportType::LinkIntegrity => 0x01,
+            ReportType::SinglePvc => 0x02,
+        }
+    }
+}
+
+/// Status of one PVC in an LMI status message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PvcStatus {
+    /// DLCI of the PVC
+    pub dlci: u16,
+    /// The PVC was not in the previous full status
+    pub new: bool,
+    /// The PVC has been deleted
+    pub deleted: bool,
+    /// The PVC can carry traffic
+    pub active: bool,
+}
+
+/// LMI message, the payload of a frame on DLCI 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LmiMessage {
+    /// Status enquiry or status
+    pub kind: LmiMessageKind,
+    /// Report type
+    pub report: ReportType,
+    /// Link integrity verification `(send, receive)` sequence numbers, if present
+    pub sequence: Option<(u8, u8)>,
+    /// PVC status elements, in the order received
+    pub pvcs: Vec<PvcStatus>,
+}
+
+impl LmiMessage {
+    /// Decodes the payload of an LMI frame, starting with its control field.  Unknown
+    /// information elements are skipped.
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MalformedMessage**: The header does not match the variant, an element
+    ///   runs past the end, or the report type is missing.
+    pub fn parse(variant: LmiVariant, payload: &[u8]) -> Result<LmiMessage, HDLCError> {
+        let malformed = || HDLCError::MalformedMessage { protocol: "LMI" };
+        let (kind, mut elements) = match payload {
+            [LMI_CONTROL, LMI_DISCRIMINATOR, LMI_CALL_REFERENCE, kind, rest @ ..] => {
+                let kind = match *kind {
+                    STATUS_ENQUIRY => LmiMessageKind::StatusEnquiry,
+                    STATUS => LmiMessageKind::Status,
+                    _ => return Err(malformed()),
+                };
+                (kind, rest)
+            }
+            _ => return Err(malformed()),
+        };
+        if variant == LmiVariant::AnsiAnnexD {
+            elements = match elements {
+                [ANSI_LOCKING_SHIFT, rest @ ..] => rest,
+                _ => return Err(malformed()),
+            };
+        }
+
+        let [report_id, integrity_id, pvc_id] = variant.elements();
+        let mut report = None;
+        let mut sequence = None;
+        let mut pvcs = Vec::new();
+        while let [id, len, rest @ ..] = elements {
+            let len = usize::from(*len);
+            let value = rest.get(..len).ok_or_else(malformed)?;
+            elements = &rest[len..];
+
+            match (*id, value) {
+                (id, [report_type, ..]) if id == report_id => {
+                    report = ReportType::from_byte(*report_type);
+                }
+                (id, [send, receive, ..]) if id == integrity_id => {
+                    sequence = Some((*send, *receive));
+                }
+                (id, [high, low, status, ..]) if id == pvc_id => pvcs.push(PvcStatus {
+                    dlci: (u16::from(high & 0x3F) << 4) | u16::from((low >> 3) & 0x0F),
+                    new: status & PVC_NEW != 0,
+                    deleted: status & PVC_DELETED != 0,
+                    active: status & PVC_ACTIVE != 0,
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(LmiMessage {
+            kind,
+            report: report.ok_or_else(malformed)?,
+            sequence,
+            pvcs,
+        })
+    }
+
+    /// Decodes the LMI message carried by a frame
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MalformedMessage**: The frame is not on DLCI 0, or its payload is
+    ///   malformed.
+    pub fn from_frame(variant: LmiVariant, frame: &Frame<'_>) -> Result<LmiMessage, HDLCError> {
+        if frame.address.dlci != 0 {
+            return Err(HDLCError::MalformedMessage { protocol: "LMI" });
+        }
+
+        LmiMessage::parse(variant, frame.payload)
+    }
+
+    /// Produces the payload, control field included
+    pub fn to_bytes(&self, variant: LmiVariant) -> Vec<u8> {
+        let kind = match self.kind {
+            LmiMessageKind::StatusEnquiry => STATUS_ENQUIRY,
+            LmiMessageKind::Status => STATUS,
+        };
+        let [report_id, integrity_id, pvc_id] = variant.elements();
+
+        let mut output = vec![LMI_CONTROL, LMI_DISCRIMINATOR, LMI_CALL_REFERENCE, kind];
+        if variant == LmiVariant::AnsiAnnexD {
+            output.push(ANSI_LOCKING_SHIFT);
+        }
+        output.extend_from_slice(&[report_id, 1, self.report.to_byte()]);
+        if let Some((send, receive)) = self.sequence {
+            output.extend_from_slice(&[integrity_id, 2, send, receive]);
+        }
+        for pvc in &self.pvcs {
+            let status = 0x80
+                | if pvc.new { PVC_NEW } else { 0 }
+                | if pvc.deleted { PVC_DELETED } else { 0 }
+                | if pvc.active { PVC_ACTIVE } else { 0 };
+            output.extend_from_slice(&[
+                pvc_id,
+                3,
+                ((pvc.dlci >> 4) & 0x3F) as u8,
+                0x80 | (((pvc.dlci & 0x0F) as u8) << 3),
+                status,
+            ]);
+        }
+
+        output
+    }
+
+    /// Produces the whole frame on DLCI 0, ready for an FCS
+    pub fn to_frame(&self, variant: LmiVariant) -> Vec<u8> {
+        let payload = self.to_bytes(variant);
+
+        Frame {
+            address: Address::new(0),
+            payload: &payload,
+        }
+        .to_bytes()
+    }
+}
+
+/// User side of LMI: polls the network and keeps the last known status of every PVC.
+///
+/// Call [`poll`](LmiPoller::poll) every polling interval (T391, 10 seconds by default) and
+/// send the enquiry it returns.  Every `full_status_every` polls (N391, 6 by default) asks for
+/// a full status.  Hand every status received to [`receive`](LmiPoller::receive).
+///
+/// ```rust
+/// use hdlc::frame_relay::{LmiMessage, LmiMessageKind, LmiPoller, PvcStatus, ReportType};
+///
+/// let mut poller = LmiPoller::default();
+/// let enquiry = poller.poll();
+///
+/// let accepted = poller.receive(&LmiMessage {
+///     kind: LmiMessageKind::Status,
+///     report: ReportType::FullStatus,
+///     sequence: Some((1, enquiry.sequence.unwrap().0)),
+///     pvcs: vec![PvcStatus { dlci: 100, new: true, deleted: false, active: true }],
+/// });
+///
+/// assert!(accepted);
+/// assert_eq!(enquiry.report, ReportType::FullStatus);
+/// assert!(poller.pvc(100).unwrap().active);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LmiPoller {
+    full_status_every: u32,
+    polls: u32,
+    // Last sequence number sent and last one received
+    send: u8,
+    receive: u8,
+    pvcs: BTreeMap<u16, PvcStatus>,
+}
+
+impl Default for LmiPoller {
+    /// Poller that asks for a full status every 6 polls
+    fn default() -> LmiPoller {
+        LmiPoller::new(6)
+    }
+}
+
+impl LmiPoller {
+    /// Creates a poller that asks for a full status on its first poll and every
+    /// `full_status_every` polls after that
+    pub fn new(full_status_every: u32) -> LmiPoller {
+        LmiPoller {
+            full_status_every: full_status_every.max(1),
+            polls: 0,
+            send: 0,
+            receive: 0,
+            pvcs: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the next status enquiry to send
+    pub fn poll(&mut self) -> LmiMessage {
+        let report = if self.polls.is_multiple_of(self.full_status_every) {
+            ReportType::FullStatus
+        } else {
+            ReportType::LinkIntegrity
+        };
+        self.polls = self.polls.wrapping_add(1);
+        self.send = next_sequence(self.send);
+
+        LmiMessage {
+            kind: LmiMessageKind::StatusEnquiry,
+            report,
+            sequence: Some((self.send, self.receive)),
+            pvcs: Vec::new(),
+        }
+    }
+
+    /// Takes in a status from the network.  A full status replaces the PVC table, a single
+    /// PVC status updates one entry.
+    ///
+    /// Returns `false` and leaves the poller as it was for anything but a status, and for a
+    /// status whose receive sequence number is not the one sent in the last enquiry.
+    pub fn receive(&mut self, message: &LmiMessage) -> bool {
+        if message.kind != LmiMessageKind::Status {
+            return false;
+        }
+        if let Some((send, receive)) = message.sequence {
+            if receive != self.send {
+                return false;
+            }
+            self.receive = send;
+        }
+        match message.report {
+            ReportType::FullStatus => {
+                self.pvcs = message.pvcs.iter().map(|pvc| (pvc.dlci, *pvc)).collect();
+            }
+            ReportType::SinglePvc => {
+                for pvc in &message.pvcs {
+                    self.pvcs.insert(pvc.dlci, *pvc);
+                }
+            }
+            ReportType::LinkIntegrity => {}
+        }
+        self.pvcs.retain(|_, pvc| !pvc.deleted);
+
+        true
+    }
+
+    /// Last known status of a PVC
+    pub fn pvc(&self, dlci: u16) -> Option<&PvcStatus> {
+        self.pvcs.get(&dlci)
+    }
+
+    /// Last known status of every PVC, by DLCI
+    pub fn pvcs(&self) -> impl Iterator<Item = &PvcStatus> {
+        self.pvcs.values()
+    }
+}
+
+/// Sequence numbers count 1 to 255, 0 is only sent before anything was received
+fn next_sequence(sequence: u8) -> u8 {
+    match sequence.wrapping_add(1) {
+        0 => 1,
+        next => next,
+    }
+}
@@ -0,0 +1,134 @@
+//! # kiss
+//! KISS TNC framing, for packet radio and telemetry modems.
+//!
+//! KISS frames are stuffed like HDLC frames with [`SpecialChars::KISS`](crate::SpecialChars::KISS)
+//! and carry no FCS.  The first byte of each frame is a command byte: the TNC port in the high
+//! nibble and the [`Command`] in the low one.  Data frames usually hold an AX.25 frame, see
+//! [`ax25`](crate::ax25).
+//!
+//! ### Send a data frame and a parameter
+//! ```rust
+//! use hdlc::kiss::{Command, Frame};
+//! use hdlc::{decode, encode, SpecialChars};
+//!
+//! let data = Frame { port: 0, command: Command::Data(&[0x01, 0xC0]) };
+//! let txdelay = Frame { port: 1, command: Command::TxDelay(50) };
+//!
+//! let wire = encode(&data.to_bytes(), SpecialChars::KISS).unwrap();
+//! assert_eq!(wire, [0xC0, 0x00, 0x01, 0xDB, 0xDC, 0xC0]);
+//! assert_eq!(txdelay.to_bytes(), [0x11, 50]);
+//!
+//! let raw = decode(&wire, SpecialChars::KISS).unwrap();
+//! assert_eq!(Frame::parse(&raw).unwrap(), data);
+//! ```
+
+use crate::HDLCError;
+
+/// Command byte that leaves KISS mode, whatever the port
+const RETURN: u8 = 0xFF;
+
+/// What a KISS frame asks of the TNC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command<'a> {
+    /// Frame to send or received on the air
+    Data(&'a [u8]),
+    /// Keyup delay, in units of 10 ms
+    TxDelay(u8),
+    /// Persistence parameter p of p-persistent CSMA, (p + 1) / 256
+    Persistence(u8),
+    /// Slot interval, in units of 10 ms
+    SlotTime(u8),
+    /// Time to hold the transmitter after the frame, in units of 10 ms.  Obsolete.
+    TxTail(u8),
+    /// Full duplex when true, CSMA half duplex otherwise
+    FullDuplex(bool),
+    /// Hardware specific settings
+    SetHardware(&'a [u8]),
+    /// Leave KISS mode
+    Return,
+}
+
+impl Command<'_> {
+    /// Command number in the low nibble of the command byte
+    #[cfg(feature = "std")]
+    fn code(&self) -> u8 {
+        match self {
+            Command::Data(_) => 0x00,
+            Command::TxDelay(_) => 0x01,
+            Command::Persistence(_) => 0x02,
+            Command::SlotTime(_) => 0x03,
+            Command::TxTail(_) => 0x04,
+            Command::FullDuplex(_) => 0x05,
+            Command::SetHardware(_) => 0x06,
+            Command::Return => 0x0F,
+        }
+    }
+}
+
+/// A decoded KISS frame split into its command byte and contents.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// TNC port, 0 to 15.  Ignored for [`Command::Return`].
+    pub port: u8,
+    /// Command and its argument
+    pub command: Command<'a>,
+}
+
+impl<'a> Frame<'a> {
+    /// Splits a decoded frame into its command byte and contents
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MalformedMessage**: The frame is empty, a parameter command has no
+    ///   value, or the command is unknown.
+    pub fn parse(frame: &'a [u8]) -> Result<Frame<'a>, HDLCError> {
+        let malformed = || HDLCError::MalformedMessage { protocol: "KISS" };
+        let (&byte, rest) = frame.split_first().ok_or_else(malformed)?;
+        if byte == RETURN {
+            return Ok(Frame {
+                port: 0,
+                command: Command::Return,
+            });
+        }
+
+        let value = || rest.first().copied().ok_or_else(malformed);
+        let command = match byte & 0x0F {
+            0x00 => Command::Data(rest),
+            0x01 => Command::TxDelay(value()?),
+            0x02 => Command::Persistence(value()?),
+            0x03 => Command::SlotTime(value()?),
+            0x04 => Command::TxTail(value()?),
+            0x05 => Command::FullDuplex(value()? != 0),
+            0x06 => Command::SetHardware(rest),
+            _ => return Err(malformed()),
+        };
+
+        Ok(Frame {
+            port: byte >> 4,
+            command,
+        })
+    }
+
+    /// Produces the frame bytes, ready for [`encode`](crate::encode) with
+    /// [`SpecialChars::KISS`](crate::SpecialChars::KISS).  The port is taken modulo 16.
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let command = match self.command {
+            Command::Return => return vec![RETURN],
+            command => ((self.port & 0x0F) << 4) | command.code(),
+        };
+
+        let mut output = vec![command];
+        match self.command {
+            Command::Data(data) | Command::SetHardware(data) => output.extend_from_slice(data),
+            Command::TxDelay(value)
+            | Command::Persistence(value)
+            | Command::SlotTime(value)
+            | Command::TxTail(value) => output.push(value),
+            Command::FullDuplex(on) => output.push(u8::from(on)),
+            Command::Return => {}
+        }
+
+        output
+    }
+}
@@ -0,0 +1,489 @@
+//! # lapd
+//! LAPD (ITU-T Q.921) frames for the ISDN D channel.
+//!
+//! LAPD uses a two byte [`Address`] made of a [`Sapi`], the C/R bit and a [`Tei`], and the
+//! modulo 128 [`ExtendedControl`] field.  Frames are encoded and decoded with the functions of
+//! the crate root and [`Fcs::Fcs16`](crate::Fcs::Fcs16).
+//!
+//! Terminals get their TEI from the network through the TEI management procedures, carried in
+//! UI frames on SAPI 63.  [`TeiMessage`] encodes those messages and [`TeiManager`] runs the
+//! user side of the procedures.
+//!
+//! ### Decode a frame
+//! ```rust
+//! use hdlc::frame::{ExtendedControl, Supervisory};
+//! use hdlc::lapd::{Frame, Sapi, Side, Tei};
+//! use hdlc::{decode_with_fcs, Fcs, SpecialChars};
+//!
+//! let wire = [0x7E, 0x02, 0x85, 0x01, 0x0B, 0xF2, 0x57, 0x7E];
+//! let raw = decode_with_fcs(&wire, SpecialChars::default(), Fcs::Fcs16).unwrap();
+//! let frame = Frame::parse(&raw).unwrap();
+//!
+//! assert_eq!(frame.address.sapi, Sapi::CALL_CONTROL);
+//! assert_eq!(frame.address.tei, Tei::new(66).unwrap());
+//! assert!(frame.address.is_command(Side::Network));
+//! assert_eq!(
+//!     frame.control,
+//!     ExtendedControl::Supervisory { kind: Supervisory::Rr, nr: 5, pf: true }
+//! );
+//! ```
+
+use crate::frame::{ExtendedControl, Unnumbered};
+use crate::HDLCError;
+
+/// Management entity identifier of TEI management messages
+const TEI_MANAGEMENT_ENTITY: u8 = 0x0F;
+
+/// Service Access Point Identifier, the 6 bit upper layer selector of an [`Address`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Sapi(u8);
+
+impl Sapi {
+    /// Call control procedures, Q.931 signalling
+    pub const CALL_CONTROL: Sapi = Sapi(0);
+    /// Packet mode communication using Q.931 call control procedures
+    pub const PACKET_MODE: Sapi = Sapi(1);
+    /// X.25 level 3 packet communication
+    pub const X25: Sapi = Sapi(16);
+    /// Layer 2 management, TEI assignment
+    pub const MANAGEMENT: Sapi = Sapi(63);
+
+    /// SAPI with the given value
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::InvalidAddress**: `value` does not fit in 6 bits.
+    pub const fn new(value: u8) -> Result<Sapi, HDLCError> {
+        match value {
+            0..=63 => Ok(Sapi(value)),
+            _ => Err(HDLCError::InvalidAddress { byte: value }),
+        }
+    }
+
+    /// Numeric value, 0 to 63
+    pub const fn value(&self) -> u8 {
+        self.0
+    }
+}
+
+/// Terminal Endpoint Identifier, the 7 bit terminal selector of an [`Address`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tei(u8);
+
+impl Tei {
+    /// Broadcast to every terminal, also the "any TEI" value of an identity request
+    pub const GROUP: Tei = Tei(127);
+
+    /// TEI with the given value
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::InvalidAddress**: `value` does not fit in 7 bits.
+    pub const fn new(value: u8) -> Result<Tei, HDLCError> {
+        match value {
+            0..=127 => Ok(Tei(value)),
+            _ => Err(HDLCError::InvalidAddress { byte: value }),
+        }
+    }
+
+    /// Numeric value, 0 to 127
+    pub const fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// The group TEI, 127
+    pub const fn is_group(&self) -> bool {
+        self.0 == Tei::GROUP.0
+    }
+
+    /// Assigned by the network through TEI management, 64 to 126
+    pub const fn is_automatic(&self) -> bool {
+        matches!(self.0, 64..=126)
+    }
+}
+
+/// End of the link a frame comes from.  The C/R bit means command on one side and response
+/// on the other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Side {
+    /// Terminal equipment, sends commands with C/R = 0
+    User,
+    /// The exchange, sends commands with C/R = 1
+    Network,
+}
+
+/// Two byte LAPD address field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Address {
+    /// Service the frame is for
+    pub sapi: Sapi,
+    /// Command/response bit, see [`Address::is_command`]
+    pub cr: bool,
+    /// Terminal the frame is for or from
+    pub tei: Tei,
+}
+
+impl Address {
+    /// Address of a command sent by `sender`
+    pub const fn command(sapi: Sapi, tei: Tei, sender: Side) -> Address {
+        Address {
+            sapi,
+            cr: matches!(sender, Side::Network),
+            tei,
+        }
+    }
+
+    /// Address of a response sent by `sender`
+    pub const fn response(sapi: Sapi, tei: Tei, sender: Side) -> Address {
+        Address {
+            sapi,
+            cr: matches!(sender, Side::User),
+            tei,
+        }
+    }
+
+    /// The frame is a command, given which side sent it
+    pub const fn is_command(&self, sender: Side) -> bool {
+        self.cr == matches!(sender, Side::Network)
+    }
+
+    /// Decodes the address at the start of a frame
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingControl**: The frame is shorter than the address.
+    /// * **HDLCError::InvalidAddress**: The address extension bits are not those of a two
+    ///   byte address.
+    pub fn parse(frame: &[u8]) -> Result<Address, HDLCError> {
+        let (first, second) = match frame {
+            [first, second, ..] => (*first, *second),
+            _ => return Err(HDLCError::MissingControl),
+        };
+        if first & 0x01 != 0 {
+            return Err(HDLCError::InvalidAddress { byte: first });
+        }
+        if second & 0x01 == 0 {
+            return Err(HDLCError::InvalidAddress { byte: second });
+        }
+
+        Ok(Address {
+            sapi: Sapi(first >> 2),
+            cr: first & 0x02 != 0,
+            tei: Tei(second >> 1),
+        })
+    }
+
+    /// Encodes the address field
+    pub const fn to_bytes(&self) -> [u8; 2] {
+        [
+            (self.sapi.0 << 2) | ((self.cr as u8) << 1),
+            (self.tei.0 << 1) | 0x01,
+        ]
+    }
+}
+
+/// A decoded LAPD frame split into its fields.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// SAPI, C/R and TEI
+    pub address: Address,
+    /// Modulo 128 control field
+    pub control: ExtendedControl,
+    /// Information field, empty for S frames and most U frames
+    pub info: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Splits a decoded frame, without its FCS, into its fields
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MissingControl**: The frame ends inside the address or control field.
+    /// * **HDLCError::InvalidAddress**: The address extension bits are wrong.
+    /// * **HDLCError::InvalidControl**: The reserved bits of an S frame are set.
+    pub fn parse(frame: &'a [u8]) -> Result<Frame<'a>, HDLCError> {
+        let address = Address::parse(frame)?;
+        let (control, info) = ExtendedControl::parse(&frame[2..])?;
+
+        Ok(Frame {
+            address,
+            control,
+            info,
+        })
+    }
+
+    /// Produces the frame bytes, ready for an FCS and [`encode`](crate::encode)
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(self.info.len() + 4);
+        output.extend_from_slice(&self.address.to_bytes());
+        output.extend_from_slice(&self.control.to_bytes());
+        output.extend_from_slice(self.info);
+
+        output
+    }
+}
+
+/// TEI management message types.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TeiMessageKind {
+    /// User asks for a TEI, Ai is 127
+    IdentityRequest,
+    /// Network assigns the TEI in Ai
+    IdentityAssigned,
+    /// Network refuses the request
+    IdentityDenied,
+    /// Network checks whether the TEI in Ai, or every TEI for 127, is in use
+    CheckRequest,
+    /// User reports the TEI in Ai is in use
+    CheckResponse,
+    /// Network removes the TEI in Ai, or every TEI for 127
+    Remove,
+    /// User asks the network to check its TEI
+    Verify,
+}
+
+impl TeiMessageKind {
+    fn from_byte(byte: u8) -> Option<TeiMessageKind> {
+        match byte {
+            1 => Some(TeiMessageKind::IdentityRequest),
+            2 => Some(TeiMessageKind::IdentityAssigned),
+            3 => Some(TeiMessageKind::IdentityDenied),
+            4 => Some(TeiMessageKind::CheckRequest),
+            5 => Some(TeiMessageKind::CheckResponse),
+            6 => Some(TeiMessageKind::Remove),
+            7 => Some(TeiMessageKind::Verify),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            TeiMessageKind::IdentityRequest => 1,
+            TeiMessageKind::IdentityAssigned => 2,
+            TeiMessageKind::IdentityDenied => 3,
+            TeiMessageKind::CheckRequest => 4,
+            TeiMessageKind::CheckResponse => 5,
+            TeiMessageKind::Remove => 6,
+            TeiMessageKind::Verify => 7,
+        }
+    }
+
+    /// Side that sends this message
+    #[cfg(feature = "std")]
+    fn sender(self) -> Side {
+        match self {
+            TeiMessageKind::IdentityRequest
+            | TeiMessageKind::CheckResponse
+            | TeiMessageKind::Verify => Side::User,
+            _ => Side::Network,
+        }
+    }
+}
+
+/// TEI management message, the information field of a UI frame on SAPI 63 and TEI 127.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TeiMessage {
+    /// Message type
+    pub kind: TeiMessageKind,
+    /// Reference number Ri, chosen at random by the user to match replies to its request
+    pub ri: u16,
+    /// Action indicator Ai, the TEI the message is about
+    pub ai: Tei,
+}
+
+impl TeiMessage {
+    /// Decodes a TEI management information field.  Only the first Ai of a check response is
+    /// kept.
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MalformedMessage**: The field is too short, is for another management
+    ///   entity, has an unknown message type or an Ai without its EA bit set.
+    pub fn parse(info: &[u8]) -> Result<TeiMessage, HDLCError> {
+        let malformed = HDLCError::MalformedMessage {
+            protocol: "TEI management",
+        };
+        match info {
+            [TEI_MANAGEMENT_ENTITY, ri_hi, ri_lo, kind, ai, ..] if ai & 0x01 != 0 => {
+                Ok(TeiMessage {
+                    kind: TeiMessageKind::from_byte(*kind).ok_or(malformed)?,
+                    ri: u16::from_be_bytes([*ri_hi, *ri_lo]),
+                    ai: Tei(ai >> 1),
+                })
+            }
+            _ => Err(malformed),
+        }
+    }
+
+    /// Decodes the message carried by a frame
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::MalformedMessage**: The frame is not a UI frame on SAPI 63, or its
+    ///   information field is malformed.
+    pub fn from_frame(frame: &Frame<'_>) -> Result<TeiMessage, HDLCError> {
+        match frame.control {
+            ExtendedControl::Unnumbered {
+                kind: Unnumbered::Ui,
+                ..
+            } if frame.address.sapi == Sapi::MANAGEMENT => TeiMessage::parse(frame.info),
+            _ => Err(HDLCError::MalformedMessage {
+                protocol: "TEI management",
+            }),
+        }
+    }
+
+    /// Produces the information field
+    pub fn to_bytes(&self) -> [u8; 5] {
+        let [ri_hi, ri_lo] = self.ri.to_be_bytes();
+
+        [
+            TEI_MANAGEMENT_ENTITY,
+            ri_hi,
+            ri_lo,
+            self.kind.to_byte(),
+            (self.ai.0 << 1) | 0x01,
+        ]
+    }
+
+    /// Produces the whole UI command frame carrying the message, ready for an FCS
+    #[cfg(feature = "std")]
+    pub fn to_frame(&self) -> Vec<u8> {
+        let info = self.to_bytes();
+
+        Frame {
+            address: Address::command(Sapi::MANAGEMENT, Tei::GROUP, self.kind.sender()),
+            control: ExtendedControl::Unnumbered {
+                kind: Unnumbered::Ui,
+                pf: false,
+            },
+            info: &info,
+        }
+        .to_bytes()
+    }
+}
+
+/// TEI state of a terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TeiState {
+    /// No TEI, the terminal cannot use point-to-point links
+    #[default]
+    Unassigned,
+    /// An identity request with this Ri is outstanding
+    Requested {
+        /// Reference number of the request
+        ri: u16,
+    },
+    /// The network assigned this TEI
+    Assigned {
+        /// Our TEI
+        tei: Tei,
+        /// Reference number of the request it was assigned for
+        ri: u16,
+    },
+}
+
+/// User side of the TEI management procedures.
+///
+/// The manager does not keep time.  Retry [`request`](TeiManager::request) when no answer
+/// comes within T202, and pick a new random Ri for every request.
+///
+/// ```rust
+/// use hdlc::lapd::{Tei, TeiManager, TeiMessage, TeiMessageKind};
+///
+/// let mut manager = TeiManager::new();
+/// let request = manager.request(0x1234);
+///
+/// let assigned = TeiMessage {
+///     kind: TeiMessageKind::IdentityAssigned,
+///     ri: request.ri,
+///     ai: Tei::new(70).unwrap(),
+/// };
+/// manager.receive(&assigned);
+///
+/// assert_eq!(manager.tei(), Some(Tei::new(70).unwrap()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TeiManager {
+    state: TeiState,
+}
+
+impl TeiManager {
+    /// Creates a manager for a terminal without a TEI
+    pub fn new() -> TeiManager {
+        TeiManager::default()
+    }
+
+    /// Starts an identity request with reference number `ri`.  Returns the message to send.
+    pub fn request(&mut self, ri: u16) -> TeiMessage {
+        self.state = TeiState::Requested { ri };
+
+        TeiMessage {
+            kind: TeiMessageKind::IdentityRequest,
+            ri,
+            ai: Tei::GROUP,
+        }
+    }
+
+    /// Asks the network to check the TEI in use.  Returns `None` without one.
+    pub fn verify(&self) -> Option<TeiMessage> {
+        self.tei().map(|tei| TeiMessage {
+            kind: TeiMessageKind::Verify,
+            ri: 0,
+            ai: tei,
+        })
+    }
+
+    /// Takes in a message from the network.  Returns the reply to send, if any.  An identity
+    /// assigned outside the automatic range, 64 to 126, is ignored.
+    pub fn receive(&mut self, message: &TeiMessage) -> Option<TeiMessage> {
+        let ours = |tei: Tei| message.ai == tei || message.ai.is_group();
+
+        match (message.kind, self.state) {
+            (TeiMessageKind::IdentityAssigned, TeiState::Requested { ri })
+                if message.ri == ri && message.ai.is_automatic() =>
+            {
+                self.state = TeiState::Assigned {
+                    tei: message.ai,
+                    ri,
+                };
+            }
+            (TeiMessageKind::IdentityDenied, TeiState::Requested { ri }) if message.ri == ri => {
+                self.state = TeiState::Unassigned;
+            }
+            // Our TEI handed to another terminal, it is no longer unique
+            (TeiMessageKind::IdentityAssigned, TeiState::Assigned { tei, ri })
+                if message.ai == tei && message.ri != ri =>
+            {
+                self.state = TeiState::Unassigned;
+            }
+            (TeiMessageKind::CheckRequest, TeiState::Assigned { tei, ri }) if ours(tei) => {
+                return Some(TeiMessage {
+                    kind: TeiMessageKind::CheckResponse,
+                    ri,
+                    ai: tei,
+                });
+            }
+            (TeiMessageKind::Remove, TeiState::Assigned { tei, .. }) if ours(tei) => {
+                self.state = TeiState::Unassigned;
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Current state
+    pub fn state(&self) -> TeiState {
+        self.state
+    }
+
+    /// The assigned TEI, if any
+    pub fn tei(&self) -> Option<Tei> {
+        match self.state {
+            TeiState::Assigned { tei, .. } => Some(tei),
+            _ => None,
+        }
+    }
+}
@@ -23,7 +23,7 @@
 //!
 //! let msg: Vec<u8> = vec![0x01, 0x7E, 0x70, 0x50, 0x00, 0x05, 0x80, 0x09];
 //! let cmp: Vec<u8> = vec![0x71, 0x01, 0x7E, 0x70, 0x50, 0x50, 0x00, 0x05, 0x80, 0x09, 0x71];
-//! let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50);
+//! let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50).unwrap();
 //!
 //! let result = encode(&msg, chars);
 //!
@@ -37,7 +37,7 @@
 //!
 //! let chars = SpecialChars::default();
 //! let msg: Vec<u8> = vec![
-//!     chars.fend, 0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09, chars.fend,
+//!     chars.fend(), 0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09, chars.fend(),
 //! ];
 //! let cmp: Vec<u8> = vec![0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09];
 //!
@@ -53,7 +53,7 @@
 //!
 //! let chars = SpecialChars::default();
 //! let mut msg = [
-//!     chars.fend, 0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09, chars.fend,
+//!     chars.fend(), 0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09, chars.fend(),
 //! ];
 //! let cmp = [0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09];
 //!
@@ -63,48 +63,108 @@
 //! assert_eq!(result.unwrap(), cmp);
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 
 use thiserror::Error;
 
-use std::collections::HashSet;
-use std::default::Default;
+use core::default::Default;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::ops::Deref;
+use core::ops::Range;
+
+use memchr::{memchr, memchr2, memchr2_iter};
+
+#[cfg(feature = "std")]
+pub use decoder::decode_frames;
+pub use decoder::{ClosingFend, DecodeOptions, OpeningFend, RuntPolicy};
+pub use fcs::Fcs;
+#[cfg(feature = "std")]
+use fcs::FcsState;
+
+#[cfg(feature = "std")]
+pub mod ax25;
+#[cfg(feature = "std")]
+pub mod chdlc;
+pub mod decoder;
+#[cfg(feature = "std")]
+pub mod encoder;
+pub mod fcs;
+pub mod frame;
+#[cfg(feature = "std")]
+pub mod frame_relay;
+pub mod kiss;
+pub mod lapd;
+#[cfg(feature = "pcap")]
+pub mod pcap;
+#[cfg(feature = "embedded-io")]
+pub mod serial;
 
 /// Special Character structure for holding the encode and decode values.
 /// IEEE standard values are defined below in Default.
 ///
+/// The four bytes are checked to be unique when the structure is built, so encoding and
+/// decoding never have to check them again.
+///
 /// # Default
 ///
 /// * **FEND**  = 0x7E;
 /// * **FESC**  = 0x7D;
 /// * **TFEND** = 0x5E;
 /// * **TFESC** = 0x5D;
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct SpecialChars {
-    /// Frame END. Byte that marks the beginning and end of a packet
-    pub fend: u8,
-    /// Frame ESCape. Byte that marks the start of a swap byte
-    pub fesc: u8,
-    /// Trade Frame END. Byte that is substituted for the FEND byte
-    pub tfend: u8,
-    /// Trade Frame ESCape. Byte that is substituted for the FESC byte
-    pub tfesc: u8,
+    // Frame END. Byte that marks the beginning and end of a packet
+    fend: u8,
+    // Frame ESCape. Byte that marks the start of a swap byte
+    fesc: u8,
+    // Trade Frame END. Byte that is substituted for the FEND byte
+    tfend: u8,
+    // Trade Frame ESCape. Byte that is substituted for the FESC byte
+    tfesc: u8,
 }
 
 impl Default for SpecialChars {
     /// Creates the default SpecialChars structure for encoding/decoding a packet
     fn default() -> SpecialChars {
-        SpecialChars {
-            fend: 0x7E,
-            fesc: 0x7D,
-            tfend: 0x5E,
-            tfesc: 0x5D,
-        }
+        SpecialChars::ISO
     }
 }
+
 impl SpecialChars {
+    /// ISO/IEC 13239 and PPP (RFC 1662) framing, the default: FEND 0x7E, FESC 0x7D,
+    /// TFEND 0x5E, TFESC 0x5D
+    pub const ISO: SpecialChars = SpecialChars::unchecked(0x7E, 0x7D, 0x5E, 0x5D);
+    /// KISS TNC framing, see [`kiss`]: FEND 0xC0, FESC 0xDB, TFEND 0xDC, TFESC 0xDD
+    pub const KISS: SpecialChars = SpecialChars::unchecked(0xC0, 0xDB, 0xDC, 0xDD);
+    /// SLIP (RFC 1055) framing: END 0xC0, ESC 0xDB, ESC_END 0xDC, ESC_ESC 0xDD.  Decode with
+    /// [`OpeningFend::Optional`] for senders that leave out the opening END.
+    pub const SLIP: SpecialChars = SpecialChars::unchecked(0xC0, 0xDB, 0xDC, 0xDD);
+    /// DLMS/COSEM HDLC (IEC 62056-46) framing, on the same bytes as [`SpecialChars::ISO`]
+    pub const DLMS: SpecialChars = SpecialChars::unchecked(0x7E, 0x7D, 0x5E, 0x5D);
+
     /// Creates a new SpecialChars structure for encoding/decoding a packet
-    pub fn new(fend: u8, fesc: u8, tfend: u8, tfesc: u8) -> SpecialChars {
+    ///
+    /// # Error
+    ///
+    /// * **HDLCError::DuplicateSpecialChar**: Two of the special characters are the same byte.
+    pub const fn new(fend: u8, fesc: u8, tfend: u8, tfesc: u8) -> Result<SpecialChars, HDLCError> {
+        if fend == fesc
+            || fend == tfend
+            || fend == tfesc
+            || fesc == tfend
+            || fesc == tfesc
+            || tfend == tfesc
+        {
+            return Err(HDLCError::DuplicateSpecialChar);
+        }
+
+        Ok(SpecialChars::unchecked(fend, fesc, tfend, tfesc))
+    }
+
+    /// Presets are known to be unique
+    const fn unchecked(fend: u8, fesc: u8, tfend: u8, tfesc: u8) -> SpecialChars {
         SpecialChars {
             fend,
             fesc,
@@ -112,10 +172,33 @@ impl SpecialChars {
             tfesc,
         }
     }
+
+    /// Frame END.  Byte that marks the beginning and end of a packet
+    pub const fn fend(&self) -> u8 {
+        self.fend
+    }
+
+    /// Frame ESCape.  Byte that marks the start of a swap byte
+    pub const fn fesc(&self) -> u8 {
+        self.fesc
+    }
+
+    /// Trade Frame END.  Byte that is substituted for the FEND byte
+    pub const fn tfend(&self) -> u8 {
+        self.tfend
+    }
+
+    /// Trade Frame ESCape.  Byte that is substituted for the FESC byte
+    pub const fn tfesc(&self) -> u8 {
+        self.tfesc
+    }
 }
 
 /// Produces escaped (encoded) message surrounded with `FEND`
 ///
+/// Payloads of any length are encoded, here and in the other free encoders.  Use
+/// [`Encoder`](encoder::Encoder) with a `max_frame_len` to refuse those over N1.
+///
 /// # Inputs
 /// * **Vec<u8>**: A vector of the bytes you want to encode
 /// * **SpecialChars**: The special characters you want to swap
@@ -124,11 +207,6 @@ impl SpecialChars {
 ///
 /// * **Result<Vec<u8>>**: Encoded output message
 ///
-/// # Error
-///
-/// * **HDLCError::DuplicateSpecialChar**: Checks special characters for duplicates, if any of
-///     the `SpecialChars` are duplicate, throw an error.  Displays "Duplicate special character".
-///
 /// # Todo
 ///
 /// Catch more errors, like an incomplete packet
@@ -139,50 +217,500 @@ impl SpecialChars {
 /// let input: Vec<u8> = vec![0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09];
 /// let op_vec = hdlc::encode(&input.to_vec(), chars);
 /// ```
+#[cfg(feature = "std")]
 pub fn encode(data: &[u8], s_chars: SpecialChars) -> Result<Vec<u8>, HDLCError> {
-    // Safety check to make sure the special character values are all unique
-    let mut set = HashSet::new();
-    if !set.insert(s_chars.fend)
-        || !set.insert(s_chars.fesc)
-        || !set.insert(s_chars.tfend)
-        || !set.insert(s_chars.tfesc)
-    {
-        return Err(HDLCError::DuplicateSpecialChar);
-    }
-
     // Prealocate for speed.  *2 is the max size it can be if EVERY char is swapped
     let mut output = Vec::with_capacity(data.len() * 2);
-    // Iterator over the input that allows peeking
-    let input_iter = data.iter();
+    encode_to_vec(data, &mut output, s_chars);
+
+    Ok(output)
+}
+
+/// Appends the escaped (encoded) message surrounded with `FEND` to an existing vector
+///
+/// # Inputs
+/// * **&[u8]**: The bytes you want to encode
+/// * **&mut Vec<u8>**: The vector the encoded message is appended to
+/// * **SpecialChars**: The special characters you want to swap
+///
+/// # Output
+///
+/// * **usize**: Number of bytes appended
+///
+/// # Example
+/// ```rust
+/// let chars = hdlc::SpecialChars::default();
+/// let mut output = vec![0xAA];
+/// let written = hdlc::encode_to_vec(&[0x01, 0x7E], &mut output, chars);
+///
+/// assert_eq!(written, 5);
+/// assert_eq!(output, [0xAA, 0x7E, 0x01, 0x7D, 0x5E, 0x7E]);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_to_vec(data: &[u8], output: &mut Vec<u8>, s_chars: SpecialChars) -> usize {
+    let start = output.len();
+    output.reserve(data.len() + 2);
 
     //Push initial FEND
     output.push(s_chars.fend);
+    stuff_to_vec(data, output, s_chars);
+    // Push final FEND
+    output.push(s_chars.fend);
+
+    output.len() - start
+}
+
+/// Produces one escaped (encoded) frame from several parts, surrounded with `FEND`
+///
+/// The parts are stuffed back to back as if they had been joined first, which saves building
+/// the joined copy.  Anything that derefs to `[u8]` works, including `&[u8]`, `Vec<u8>` and
+/// `std::io::IoSlice`.
+///
+/// # Inputs
+/// * **&[P]**: The parts you want to encode, in order
+/// * **SpecialChars**: The special characters you want to swap
+///
+/// # Output
+///
+/// * **Vec<u8>**: Encoded output message
+///
+/// # Example
+/// ```rust
+/// use std::io::IoSlice;
+///
+/// let chars = hdlc::SpecialChars::default();
+/// let header = [0xFF, 0x03];
+/// let payload = [0x7E, 0x01];
+///
+/// let joined = hdlc::encode(&[0xFF, 0x03, 0x7E, 0x01], chars).unwrap();
+/// let parts = hdlc::encode_parts(&[&header[..], &payload[..]], chars);
+/// let vectored = hdlc::encode_parts(&[IoSlice::new(&header), IoSlice::new(&payload)], chars);
+///
+/// assert_eq!(parts, joined);
+/// assert_eq!(vectored, joined);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_parts<P>(parts: &[P], s_chars: SpecialChars) -> Vec<u8>
+where
+    P: Deref<Target = [u8]>,
+{
+    // Prealocate for speed.  *2 is the max size it can be if EVERY char is swapped
+    let total: usize = parts.iter().map(|part| part.len()).sum();
+    let mut output = Vec::with_capacity(total * 2 + 2);
+
+    output.push(s_chars.fend);
+    for part in parts {
+        stuff_to_vec(part, &mut output, s_chars);
+    }
+    output.push(s_chars.fend);
+
+    output
+}
+
+/// Produces escaped (encoded) message with its Frame Check Sequence, surrounded with `FEND`
+///
+/// The FCS is computed over `data` while it is stuffed, then appended least significant byte
+/// first and stuffed like the rest of the frame.  As with [`encode`], the length of `data` is
+/// not limited.
+///
+/// # Inputs
+/// * **&[u8]**: The bytes you want to encode
+/// * **SpecialChars**: The special characters you want to swap
+/// * **Fcs**: The Frame Check Sequence to append
+///
+/// # Output
+///
+/// * **Vec<u8>**: Encoded output message
+///
+/// # Example
+/// ```rust
+/// use hdlc::{encode_with_fcs, Fcs, SpecialChars};
+///
+/// let result = encode_with_fcs(&[0xFF, 0x03], SpecialChars::default(), Fcs::Fcs16);
+///
+/// assert_eq!(result, [0x7E, 0xFF, 0x03, 0x1C, 0xC2, 0x7E]);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_with_fcs(data: &[u8], s_chars: SpecialChars, fcs: Fcs) -> Vec<u8> {
+    encode_parts_with_fcs(&[data], s_chars, fcs)
+}
+
+/// Produces one escaped (encoded) frame from several parts, with a Frame Check Sequence
+/// computed across all of them, surrounded with `FEND`
+///
+/// See [`encode_parts`] for the parts and [`encode_with_fcs`] for the FCS.
+///
+/// # Example
+/// ```rust
+/// use hdlc::{encode_parts_with_fcs, encode_with_fcs, Fcs, SpecialChars};
+///
+/// let chars = SpecialChars::default();
+/// let parts = encode_parts_with_fcs(&[&[0xFF][..], &[0x03][..]], chars, Fcs::Fcs32);
+///
+/// assert_eq!(parts, encode_with_fcs(&[0xFF, 0x03], chars, Fcs::Fcs32));
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_parts_with_fcs<P>(parts: &[P], s_chars: SpecialChars, fcs: Fcs) -> Vec<u8>
+where
+    P: Deref<Target = [u8]>,
+{
+    // Prealocate for speed.  *2 is the max size it can be if EVERY char is swapped
+    let total: usize = parts.iter().map(|part| part.len()).sum::<usize>() + fcs.size();
+    let mut output = Vec::with_capacity(total * 2 + 2);
+    let mut state = FcsState::new(fcs);
+
+    output.push(s_chars.fend);
+    for part in parts {
+        state.update(part);
+        stuff_to_vec(part, &mut output, s_chars);
+    }
+    stuff_to_vec(&state.finish()[..fcs.size()], &mut output, s_chars);
+    output.push(s_chars.fend);
+
+    output
+}
+
+/// Writes the escaped (encoded) message surrounded with `FEND` into a caller supplied buffer
+///
+/// # Inputs
+/// * **&[u8]**: The bytes you want to encode
+/// * **&mut [u8]**: The buffer to write the encoded message into
+/// * **SpecialChars**: The special characters you want to swap
+///
+/// # Output
+///
+/// * **Result<usize>**: Number of bytes written to the start of the buffer
+///
+/// # Error
+///
+/// * **HDLCError::BufferTooSmall**: The encoded message does not fit in the buffer.  Use
+///   [`encoded_len`] to size it.
+///
+/// # Example
+/// ```rust
+/// let chars = hdlc::SpecialChars::default();
+/// let mut output = [0u8; 16];
+/// let written = hdlc::encode_into(&[0x01, 0x7D], &mut output, chars).unwrap();
+///
+/// assert_eq!(&output[..written], [0x7E, 0x01, 0x7D, 0x5D, 0x7E]);
+/// ```
+pub fn encode_into(
+    data: &[u8],
+    output: &mut [u8],
+    s_chars: SpecialChars,
+) -> Result<usize, HDLCError> {
+    let mut out = OutCursor { output, written: 0 };
+    let mut rest = data;
+
+    out.put(&[s_chars.fend])?;
+    // Copy whole runs between special bytes instead of checking every byte
+    while let Some(pos) = find_special(s_chars, rest) {
+        out.put(&rest[..pos])?;
+        out.put(&[s_chars.fesc, trade_char(rest[pos], s_chars)])?;
+        rest = &rest[pos + 1..];
+    }
+    out.put(rest)?;
+    out.put(&[s_chars.fend])?;
+
+    Ok(out.written)
+}
+
+/// Write position in a caller supplied output buffer
+struct OutCursor<'a> {
+    output: &'a mut [u8],
+    written: usize,
+}
+
+impl OutCursor<'_> {
+    fn put(&mut self, bytes: &[u8]) -> Result<(), HDLCError> {
+        let end = self.written + bytes.len();
+        match self.output.get_mut(self.written..end) {
+            Some(dest) => dest.copy_from_slice(bytes),
+            None => return Err(HDLCError::BufferTooSmall),
+        }
+        self.written = end;
+
+        Ok(())
+    }
+}
+
+/// Produces a lazy iterator over the escaped (encoded) message surrounded with `FEND`
+///
+/// # Inputs
+/// * **&[u8]**: The bytes you want to encode
+/// * **SpecialChars**: The special characters you want to swap
+///
+/// # Output
+///
+/// * **EncodeIter**: Iterator yielding the encoded bytes one at a time
+///
+/// # Example
+/// ```rust
+/// let chars = hdlc::SpecialChars::default();
+/// let encoded: Vec<u8> = hdlc::encode_iter(&[0x01, 0x7E], chars).collect();
+///
+/// assert_eq!(encoded, [0x7E, 0x01, 0x7D, 0x5E, 0x7E]);
+/// ```
+pub fn encode_iter(data: &[u8], s_chars: SpecialChars) -> EncodeIter<'_> {
+    EncodeIter::new(data, s_chars)
+}
+
+/// Exact length of the encoded message, both `FEND` included
+///
+/// # Example
+/// ```rust
+/// let chars = hdlc::SpecialChars::default();
+///
+/// assert_eq!(hdlc::encoded_len(&[0x01, 0x7E, 0x7D], chars), 7);
+/// ```
+pub fn encoded_len(data: &[u8], s_chars: SpecialChars) -> usize {
+    let escaped = memchr2_iter(s_chars.fend, s_chars.fesc, data).count();
+
+    data.len() + escaped + 2
+}
+
+/// Produces the abort sequence, a `fesc` followed by a `fend`
+///
+/// Sending it in place of the rest of a frame tells the receiver to throw the frame away.  The
+/// `fend` also opens the next frame.
+///
+/// # Example
+/// ```rust
+/// let chars = hdlc::SpecialChars::default();
+///
+/// assert_eq!(hdlc::abort_sequence(chars), [0x7D, 0x7E]);
+/// ```
+pub const fn abort_sequence(s_chars: SpecialChars) -> [u8; 2] {
+    [s_chars.fesc, s_chars.fend]
+}
+
+/// Lazy iterator over an encoded message, created by [`encode_iter`].
+#[derive(Debug, Clone)]
+pub struct EncodeIter<'a> {
+    data: core::slice::Iter<'a, u8>,
+    s_chars: SpecialChars,
+    // Trade byte still owed after a FESC
+    pending: Option<u8>,
+    started: bool,
+    finished: bool,
+}
+
+impl<'a> EncodeIter<'a> {
+    fn new(data: &'a [u8], s_chars: SpecialChars) -> EncodeIter<'a> {
+        EncodeIter {
+            data: data.iter(),
+            s_chars,
+            pending: None,
+            started: false,
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for EncodeIter<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if !self.started {
+            self.started = true;
+            return Some(self.s_chars.fend);
+        }
+        if let Some(trade) = self.pending.take() {
+            return Some(trade);
+        }
 
-    // Loop over every byte of the message
-    for value in input_iter {
-        match *value {
-            // FEND and FESC
-            val if val == s_chars.fesc => {
-                output.push(s_chars.fesc);
-                output.push(s_chars.tfesc);
+        match self.data.next() {
+            Some(&val) if val == self.s_chars.fesc => {
+                self.pending = Some(self.s_chars.tfesc);
+                Some(self.s_chars.fesc)
+            }
+            Some(&val) if val == self.s_chars.fend => {
+                self.pending = Some(self.s_chars.tfend);
+                Some(self.s_chars.fesc)
             }
-            val if val == s_chars.fend => {
-                output.push(s_chars.fesc);
-                output.push(s_chars.tfend);
+            Some(&val) => Some(val),
+            None if !self.finished => {
+                self.finished = true;
+                Some(self.s_chars.fend)
             }
-            // Handle any other bytes
-            _ => output.push(*value),
+            None => None,
         }
     }
 
-    // Push final FEND
-    output.push(s_chars.fend);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.data.len();
+        let flags = usize::from(!self.started) + usize::from(!self.finished);
+        let pending = usize::from(self.pending.is_some());
+        let low = remaining + flags + pending;
 
-    Ok(output)
+        (low, Some(low + remaining))
+    }
+}
+
+impl core::iter::FusedIterator for EncodeIter<'_> {}
+
+/// Escapes `data` onto the end of `output`, without any `FEND`
+#[cfg(feature = "std")]
+pub(crate) fn stuff_to_vec(data: &[u8], output: &mut Vec<u8>, s_chars: SpecialChars) {
+    let mut rest = data;
+
+    // Copy whole runs between special bytes instead of checking every byte
+    while let Some(pos) = find_special(s_chars, rest) {
+        let (run, special) = (&rest[..pos], rest[pos]);
+        output.extend_from_slice(run);
+        output.extend_from_slice(&[s_chars.fesc, trade_char(special, s_chars)]);
+        rest = &rest[pos + 1..];
+    }
+    output.extend_from_slice(rest);
+}
+
+/// Position of the next `fend` or `fesc` in `haystack`
+///
+/// Escapes often come in clusters, where setting up a vectorized search for every byte costs
+/// more than it saves, so the next byte is checked on its own first.  `memchr2` takes over for
+/// the long plain runs that make up most traffic.
+#[inline]
+pub(crate) fn find_special(s_chars: SpecialChars, haystack: &[u8]) -> Option<usize> {
+    match haystack.first() {
+        Some(&b) if b == s_chars.fend || b == s_chars.fesc => Some(0),
+        Some(_) => memchr2(s_chars.fend, s_chars.fesc, &haystack[1..]).map(|pos| pos + 1),
+        None => None,
+    }
+}
+
+/// Unescapes `data`, which holds no `FEND` and is closed by one, onto the end of `output`
+#[cfg(feature = "std")]
+pub(crate) fn unstuff_to_vec(
+    data: &[u8],
+    output: &mut Vec<u8>,
+    s_chars: SpecialChars,
+    max_frame_len: Option<usize>,
+) -> Result<(), HDLCError> {
+    let start = output.len();
+    let mut rest = data;
+
+    // Copy whole runs between escapes instead of checking every byte
+    while let Some(pos) = memchr(s_chars.fesc, rest) {
+        let offset = data.len() - rest.len();
+        check_frame_len(output.len() - start, pos, offset, max_frame_len)?;
+        output.extend_from_slice(&rest[..pos]);
+        check_frame_len(output.len() - start, 1, offset + pos, max_frame_len)?;
+        match rest.get(pos + 1) {
+            Some(&val) if val == s_chars.tfend => output.push(s_chars.fend),
+            Some(&val) if val == s_chars.tfesc => output.push(s_chars.fesc),
+            Some(&val) => {
+                return Err(HDLCError::MissingTradeChar {
+                    offset: data.len() - rest.len() + pos + 1,
+                    byte: Some(val),
+                    frame: 0,
+                })
+            }
+            // The closing FEND follows the FESC
+            None => {
+                return Err(HDLCError::Aborted {
+                    offset: data.len() - rest.len() + pos,
+                    frame: 0,
+                })
+            }
+        }
+        rest = &rest[pos + 2..];
+    }
+    let offset = data.len() - rest.len();
+    check_frame_len(output.len() - start, rest.len(), offset, max_frame_len)?;
+    output.extend_from_slice(rest);
+
+    Ok(())
+}
+
+/// Fails if `run` more bytes, starting at `offset` in the input, make a frame of `len` bytes
+/// longer than `max_frame_len`
+fn check_frame_len(
+    len: usize,
+    run: usize,
+    offset: usize,
+    max_frame_len: Option<usize>,
+) -> Result<(), HDLCError> {
+    match max_frame_len {
+        Some(max) if len + run > max => Err(HDLCError::FrameTooLong {
+            offset: offset + (max - len),
+            max,
+            frame: 0,
+        }),
+        _ => Ok(()),
+    }
+}
+
+impl HDLCError {
+    /// Moves the position of an error found in a frame to where the frame sits in a larger input
+    #[cfg(feature = "std")]
+    pub(crate) fn relocate(self, base: usize, frame: usize) -> HDLCError {
+        match self {
+            HDLCError::FendCharInData { offset, .. } => HDLCError::FendCharInData {
+                offset: base + offset,
+                frame,
+            },
+            HDLCError::MissingTradeChar { offset, byte, .. } => HDLCError::MissingTradeChar {
+                offset: base + offset,
+                byte,
+                frame,
+            },
+            HDLCError::Aborted { offset, .. } => HDLCError::Aborted {
+                offset: base + offset,
+                frame,
+            },
+            HDLCError::FrameTooLong { offset, max, .. } => HDLCError::FrameTooLong {
+                offset: base + offset,
+                max,
+                frame,
+            },
+            HDLCError::RuntFrame {
+                offset, len, min, ..
+            } => HDLCError::RuntFrame {
+                offset: base + offset,
+                len,
+                min,
+                frame,
+            },
+            HDLCError::MissingFinalFend { offset, .. } => HDLCError::MissingFinalFend {
+                offset: base + offset,
+                frame,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Offending byte for error messages
+fn describe_byte(byte: &Option<u8>) -> DescribeByte {
+    DescribeByte(*byte)
+}
+
+/// Formats a byte, or the end of the input for `None`, without allocating
+struct DescribeByte(Option<u8>);
+
+impl fmt::Display for DescribeByte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(byte) => write!(f, "{:#04X}", byte),
+            None => f.write_str("the end of the input"),
+        }
+    }
+}
+
+/// Trade byte that replaces a `fend` or `fesc` after the escape
+fn trade_char(special: u8, s_chars: SpecialChars) -> u8 {
+    if special == s_chars.fend {
+        s_chars.tfend
+    } else {
+        s_chars.tfesc
+    }
 }
 
 /// Produces unescaped (decoded) message without `FEND` characters.
 ///
+/// Runs of `FEND` around the frame are inter-frame fill, so doubled and trailing flags are
+/// accepted.
+///
 /// # Inputs
 /// * **Vec<u8>**: A vector of the bytes you want to decode
 /// * **SpecialChars**: The special characters you want to swap
@@ -193,12 +721,11 @@ pub fn encode(data: &[u8], s_chars: SpecialChars) -> Result<Vec<u8>, HDLCError>
 ///
 /// # Error
 ///
-/// * **HDLCError::DuplicateSpecialChar**: Checks special characters for duplicates, if any of
-///     the `SpecialChars` are duplicate, throw an error.  Displays "Duplicate special character".
 /// * **HDLCError::FendCharInData**: Checks to make sure the full decoded message is the full
-///     length.  Found the `SpecialChars::fend` inside the message.
+///   length.  Found the `SpecialChars::fend` inside the message.
 /// * **HDLCError::MissingTradeChar**: Checks to make sure every frame escape character `fesc`
-///     is followed by either a `tfend` or a `tfesc`.
+///   is followed by either a `tfend` or a `tfesc`.
+/// * **HDLCError::Aborted**: The sender aborted the frame with a `fesc` followed by a `fend`.
 /// * **HDLCError::MissingFirstFend**: Input vector is missing a first `SpecialChars::fend`
 /// * **HDLCError::MissingFinalFend**: Input vector is missing a final `SpecialChars::fend`
 ///
@@ -212,61 +739,117 @@ pub fn encode(data: &[u8], s_chars: SpecialChars) -> Result<Vec<u8>, HDLCError>
 /// let input: Vec<u8> = vec![ 0x7E, 0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09, 0x7E];
 /// let op_vec = hdlc::decode(&input.to_vec(), chars);
 /// ```
+#[cfg(feature = "std")]
 pub fn decode(input: &[u8], s_chars: SpecialChars) -> Result<Vec<u8>, HDLCError> {
-    // Safety check to make sure the special character values are all unique
-    let mut set = HashSet::new();
-    if !set.insert(s_chars.fend)
-        || !set.insert(s_chars.fesc)
-        || !set.insert(s_chars.tfend)
-        || !set.insert(s_chars.tfesc)
-    {
-        return Err(HDLCError::DuplicateSpecialChar);
-    }
-
-    // Predefine the vector for speed
-    let mut output: Vec<u8> = Vec::with_capacity(input.len());
-    // Iterator over the input that allows peeking
-    let mut input_iter = input.iter().peekable();
-    // Tracks whether input contains a final FEND
-    let mut has_final_fend = false;
-
-    // Verify input begins with a FEND
-    if input_iter.next() != Some(&s_chars.fend) {
-        return Err(HDLCError::MissingFirstFend);
-    }
-
-    // Loop over every byte of the message
-    while let Some(value) = input_iter.next() {
-        match *value {
-            // Handle a FESC
-            val if val == s_chars.fesc => match input_iter.next() {
-                Some(&val) if val == s_chars.tfend => output.push(s_chars.fend),
-                Some(&val) if val == s_chars.tfesc => output.push(s_chars.fesc),
-                _ => return Err(HDLCError::MissingTradeChar),
-            },
-            // Handle a FEND
-            val if val == s_chars.fend => {
-                if input_iter.peek().is_none() {
-                    has_final_fend = true;
-                } else {
-                    return Err(HDLCError::FendCharInData);
-                }
-            }
-            // Handle any other bytes
-            _ => output.push(*value),
-        }
-    }
+    decode_with_options(input, s_chars, DecodeOptions::default())
+}
 
-    // If the message had a final FEND, return the message
-    if has_final_fend {
-        Ok(output)
-    } else {
-        Err(HDLCError::MissingFinalFend)
-    }
+/// Produces unescaped (decoded) message without `FEND` characters, within the limits set by
+/// `options`.
+///
+/// # Inputs
+/// * **&[u8]**: The bytes you want to decode
+/// * **SpecialChars**: The special characters you want to swap
+/// * **DecodeOptions**: Limits on the decoded frame
+///
+/// # Output
+///
+/// * **Result<Vec<u8>>**: Decoded output message
+///
+/// # Error
+///
+/// * Every error of [`decode`]
+/// * **HDLCError::FrameTooLong**: The decoded frame is longer than `options.max_frame_len`.
+///   Decoding stops there, so the output never grows past the limit.
+/// * **HDLCError::RuntFrame**: The decoded frame is shorter than `options.min_frame_len`.
+///   With [`RuntPolicy::Skip`], runts followed by another frame are skipped as fill instead.
+///
+/// `options` also sets how strict the framing is.  [`OpeningFend::Hunt`] drops bytes before
+/// the first `fend` and [`OpeningFend::Optional`] decodes from the first byte, instead of
+/// failing with `MissingFirstFend`.  [`ClosingFend::Optional`] lets the end of the input close
+/// the frame, and `trailing_partial` ignores the start of a next frame after the closing `fend`.
+///
+/// # Example
+/// ```rust
+/// use hdlc::{decode_with_options, DecodeOptions, HDLCError, SpecialChars};
+///
+/// let options = DecodeOptions {
+///     max_frame_len: Some(2),
+///     ..DecodeOptions::default()
+/// };
+/// let input = [0x7E, 0x01, 0x02, 0x03, 0x7E];
+/// let result = decode_with_options(&input, SpecialChars::default(), options);
+///
+/// assert_eq!(
+///     result.unwrap_err(),
+///     HDLCError::FrameTooLong { offset: 3, max: 2, frame: 0 }
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_with_options(
+    input: &[u8],
+    s_chars: SpecialChars,
+    options: DecodeOptions,
+) -> Result<Vec<u8>, HDLCError> {
+    // Predefine the vector for speed, no larger than the longest frame allowed
+    let capacity = options
+        .max_frame_len
+        .map_or(input.len(), |max| max.min(input.len()));
+    let mut buffer = VecBuffer {
+        input,
+        output: Vec::with_capacity(capacity),
+    };
+    decode_frame(&mut buffer, s_chars, options)?;
+
+    Ok(buffer.output)
+}
+
+/// Produces unescaped (decoded) message without `FEND` characters, after checking and
+/// removing its Frame Check Sequence.
+///
+/// # Inputs
+/// * **&[u8]**: The bytes you want to decode
+/// * **SpecialChars**: The special characters you want to swap
+/// * **Fcs**: The Frame Check Sequence the frame ends with
+///
+/// # Output
+///
+/// * **Result<Vec<u8>>**: Decoded output message, without the FCS
+///
+/// # Error
+///
+/// * Every error of [`decode`]
+/// * **HDLCError::MissingFcs**: The decoded frame is shorter than the FCS.
+/// * **HDLCError::FcsMismatch**: The received FCS does not match the one computed over the
+///   frame.
+///
+/// # Example
+/// ```rust
+/// use hdlc::{decode_with_fcs, Fcs, SpecialChars};
+///
+/// let input = [0x7E, 0xFF, 0x03, 0x1C, 0xC2, 0x7E];
+/// let result = decode_with_fcs(&input, SpecialChars::default(), Fcs::Fcs16);
+///
+/// assert_eq!(result.unwrap(), [0xFF, 0x03]);
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_with_fcs(
+    input: &[u8],
+    s_chars: SpecialChars,
+    fcs: Fcs,
+) -> Result<Vec<u8>, HDLCError> {
+    let mut output = decode(input, s_chars)?;
+    let len = check_fcs(&output, fcs)?;
+    output.truncate(len);
+
+    Ok(output)
 }
 
 /// Produces slice (`&[u8]`) unescaped (decoded) message without `FEND` characters.
 ///
+/// Runs of `FEND` around the frame are inter-frame fill, so doubled and trailing flags are
+/// accepted.
+///
 /// # Inputs
 /// * **&mut [u8]**: A mutable slice of the bytes you want to decode
 /// * **SpecialChars**: The special characters you want to swap
@@ -277,12 +860,12 @@ pub fn decode(input: &[u8], s_chars: SpecialChars) -> Result<Vec<u8>, HDLCError>
 ///
 /// # Error
 ///
-/// * **HDLCError::DuplicateSpecialChar**: Checks special characters for duplicates, if any of
-///     the `SpecialChars` are duplicate, throw an error.  Displays "Duplicate special character".
 /// * **HDLCError::FendCharInData**: Checks to make sure the full decoded message is the full
-///     length.  Found the `SpecialChars::fend` inside the message.
+///   length.  Found the `SpecialChars::fend` inside the message.
 /// * **HDLCError::MissingTradeChar**: Checks to make sure every frame escape character `fesc`
-///     is followed by either a `tfend` or a `tfesc`.
+///   is followed by either a `tfend` or a `tfesc`.
+/// * **HDLCError::Aborted**: The sender aborted the frame with a `fesc` followed by a `fend`.
+/// * **HDLCError::MissingFirstFend**: Input vector is missing a first `SpecialChars::fend`
 /// * **HDLCError::MissingFinalFend**: Input vector is missing a final `SpecialChars::fend`
 ///
 /// # Todo
@@ -296,67 +879,253 @@ pub fn decode(input: &[u8], s_chars: SpecialChars) -> Result<Vec<u8>, HDLCError>
 /// let op_vec = hdlc::decode_slice(&mut input, chars);
 /// ```
 pub fn decode_slice(input: &mut [u8], s_chars: SpecialChars) -> Result<&[u8], HDLCError> {
-    // Safety check to make sure the special character values are all unique
-    let mut set = HashSet::new();
-    if !set.insert(s_chars.fend)
-        || !set.insert(s_chars.fesc)
-        || !set.insert(s_chars.tfend)
-        || !set.insert(s_chars.tfesc)
-    {
-        return Err(HDLCError::DuplicateSpecialChar);
-    }
-
-    // Define the counting variables for proper loop functionality
-    let mut sync = 0;
-    let mut swap = 0;
-    let mut last_was_fesc = 0;
-    let input_length = input.len();
-
-    // Predefine the vector for iterator
-    let mut output: Vec<u8> = Vec::with_capacity(input_length);
-    output.extend_from_slice(input);
-
-    for (index, byte) in output.iter().enumerate() {
-        //println!("D={}, B={} S={}  Output{:?}", index, byte, swap, input);
-        // Handle the special escape characters
-        if last_was_fesc > 0 {
-            if *byte == s_chars.tfesc {
-                swap += 1;
-                input[index - swap - 1] = s_chars.fesc;
-            } else if *byte == s_chars.tfend {
-                swap += 1;
-                input[index - swap - 1] = s_chars.fend;
-            } else {
-                return Err(HDLCError::MissingTradeChar);
+    decode_slice_with_options(input, s_chars, DecodeOptions::default())
+}
+
+/// Produces slice (`&[u8]`) unescaped (decoded) message without `FEND` characters, within the
+/// limits set by `options`.
+///
+/// Errors and framing options are those of [`decode_slice`] and [`decode_with_options`].
+///
+/// # Example
+/// ```rust
+/// use hdlc::{decode_slice_with_options, DecodeOptions, SpecialChars};
+///
+/// let options = DecodeOptions {
+///     max_frame_len: Some(4),
+///     ..DecodeOptions::default()
+/// };
+/// let mut input = [0x7E, 0x01, 0x7D, 0x5E, 0x02, 0x7E];
+/// let result = decode_slice_with_options(&mut input, SpecialChars::default(), options);
+///
+/// assert_eq!(result.unwrap(), [0x01, 0x7E, 0x02]);
+/// ```
+pub fn decode_slice_with_options(
+    input: &mut [u8],
+    s_chars: SpecialChars,
+    options: DecodeOptions,
+) -> Result<&[u8], HDLCError> {
+    let mut buffer = SliceBuffer { input, len: 0 };
+    decode_frame(&mut buffer, s_chars, options)?;
+
+    let SliceBuffer { input, len } = buffer;
+    Ok(&input[..len])
+}
+
+/// Input being decoded and the frame decoded from it so far, for [`decode_frame`]
+trait DecodeBuffer {
+    /// The escaped input
+    fn input(&self) -> &[u8];
+    /// Length of the decoded frame
+    fn len(&self) -> usize;
+    /// Appends `input[range]` to the decoded frame
+    fn extend(&mut self, range: Range<usize>);
+    /// Appends an unescaped byte to the decoded frame
+    fn push(&mut self, byte: u8);
+    /// Drops the decoded frame, to start over with the next one
+    fn clear(&mut self);
+}
+
+/// Decodes into a new vector
+#[cfg(feature = "std")]
+struct VecBuffer<'a> {
+    input: &'a [u8],
+    output: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl DecodeBuffer for VecBuffer<'_> {
+    fn input(&self) -> &[u8] {
+        self.input
+    }
+
+    fn len(&self) -> usize {
+        self.output.len()
+    }
+
+    fn extend(&mut self, range: Range<usize>) {
+        self.output.extend_from_slice(&self.input[range]);
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.output.push(byte);
+    }
+
+    fn clear(&mut self) {
+        self.output.clear();
+    }
+}
+
+/// Decodes in place, the frame is written over the start of the input.  Every write lands
+/// behind the byte being read, since flags and escapes only ever shrink the frame.
+struct SliceBuffer<'a> {
+    input: &'a mut [u8],
+    len: usize,
+}
+
+impl DecodeBuffer for SliceBuffer<'_> {
+    fn input(&self) -> &[u8] {
+        self.input
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn extend(&mut self, range: Range<usize>) {
+        let run = range.len();
+        self.input.copy_within(range, self.len);
+        self.len += run;
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.input[self.len] = byte;
+        self.len += 1;
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+/// Decodes the one frame in the input of `buffer`, the shared core of [`decode_with_options`]
+/// and [`decode_slice_with_options`]
+fn decode_frame<B: DecodeBuffer>(
+    buffer: &mut B,
+    s_chars: SpecialChars,
+    options: DecodeOptions,
+) -> Result<(), HDLCError> {
+    let max_frame_len = options.max_frame_len;
+    let min_frame_len = options.min_frame_len;
+    let input_len = buffer.input().len();
+
+    // Verify input begins with a FEND, unless the options skip to it or make it optional
+    let first = buffer.input().first().copied();
+    let missing = || HDLCError::MissingFirstFend { byte: first };
+    let mut index = match first {
+        Some(byte) if byte == s_chars.fend => 1,
+        Some(_) if options.opening_fend == OpeningFend::Optional => 0,
+        Some(_) if options.opening_fend == OpeningFend::Hunt => {
+            memchr(s_chars.fend, buffer.input()).ok_or_else(missing)? + 1
+        }
+        _ => return Err(missing()),
+    };
+
+    // Copy whole runs between special bytes instead of checking every byte
+    while let Some(pos) = find_special(s_chars, &buffer.input()[index..]) {
+        let pos = index + pos;
+        check_frame_len(buffer.len(), pos - index, index, max_frame_len)?;
+        buffer.extend(index..pos);
+        let special = buffer.input()[pos];
+        index = pos + 1;
+
+        // Handle a FEND, only fill may follow the closing FEND unless a trailing partial frame
+        // is allowed
+        if special == s_chars.fend {
+            // Scan the run of FENDs once, so long idle fill stays linear
+            let run = buffer.input()[index..]
+                .iter()
+                .position(|&b| b != s_chars.fend);
+            let fill_only = run.is_none();
+            // A FEND before any data is fill after the opening FEND
+            if let (0, Some(run)) = (buffer.len(), run) {
+                index += run;
+                continue;
             }
-            last_was_fesc = 0
-        } else {
-            // Match based on the special characters, but struct fields are not patterns and cant match
-            if *byte == s_chars.fend {
-                // If we are already synced, this is the closing sync char
-                if sync > 0 {
-                    // Check to make sure the full message was decoded
-                    if (index + 1) < input_length {
-                        return Err(HDLCError::FendCharInData);
-                    }
-                    // Minus 1 because indexing starts at 0
-                    let end = index - swap - 1;
-                    return Ok(&input[..end]);
-
-                // Todo: Maybe save for a 2nd message?  I currently throw an error above
-                } else {
-                    sync = 1;
+            if buffer.len() < min_frame_len {
+                // A skipped runt is fill, unless there is nothing else
+                if let (RuntPolicy::Skip, Some(run)) = (options.runts, run) {
+                    buffer.clear();
+                    index += run;
+                    continue;
                 }
-            } else if *byte == s_chars.fesc {
-                last_was_fesc = 1;
-            } else if sync > 0 {
-                // Minus 1 because indexing starts at 0
-                input[index - swap - 1] = *byte;
+                return Err(HDLCError::RuntFrame {
+                    offset: pos,
+                    len: buffer.len(),
+                    min: min_frame_len,
+                    frame: 0,
+                });
+            }
+            if fill_only || options.trailing_partial {
+                return Ok(());
+            }
+            return Err(HDLCError::FendCharInData {
+                offset: pos,
+                frame: 0,
+            });
+        }
+
+        // Handle a FESC
+        check_frame_len(buffer.len(), 1, pos, max_frame_len)?;
+        match buffer.input().get(index).copied() {
+            Some(val) if val == s_chars.tfend => buffer.push(s_chars.fend),
+            Some(val) if val == s_chars.tfesc => buffer.push(s_chars.fesc),
+            Some(val) if val == s_chars.fend => {
+                return Err(HDLCError::Aborted {
+                    offset: pos,
+                    frame: 0,
+                })
+            }
+            byte => {
+                return Err(HDLCError::MissingTradeChar {
+                    offset: index,
+                    byte,
+                    frame: 0,
+                })
             }
         }
+        index += 1;
     }
 
-    Err(HDLCError::MissingFinalFend)
+    // The message ran out before a final FEND
+    check_frame_len(buffer.len(), input_len - index, index, max_frame_len)?;
+    if options.closing_fend == ClosingFend::Required {
+        return Err(HDLCError::MissingFinalFend {
+            offset: input_len,
+            frame: 0,
+        });
+    }
+    buffer.extend(index..input_len);
+    if buffer.len() < min_frame_len {
+        return Err(HDLCError::RuntFrame {
+            offset: input_len,
+            len: buffer.len(),
+            min: min_frame_len,
+            frame: 0,
+        });
+    }
+
+    Ok(())
+}
+
+/// Produces slice (`&[u8]`) unescaped (decoded) message without `FEND` characters, after
+/// checking and removing its Frame Check Sequence.
+///
+/// Errors are those of [`decode_slice`] and [`decode_with_fcs`].
+///
+/// # Example
+/// ```rust
+/// use hdlc::{decode_slice_with_fcs, Fcs, SpecialChars};
+///
+/// let mut input = [0x7E, 0xFF, 0x03, 0x1C, 0xC2, 0x7E];
+/// let result = decode_slice_with_fcs(&mut input, SpecialChars::default(), Fcs::Fcs16);
+///
+/// assert_eq!(result.unwrap(), [0xFF, 0x03]);
+/// ```
+pub fn decode_slice_with_fcs<'a>(
+    input: &'a mut [u8],
+    s_chars: SpecialChars,
+    fcs: Fcs,
+) -> Result<&'a [u8], HDLCError> {
+    let output = decode_slice(input, s_chars)?;
+    let len = check_fcs(output, fcs)?;
+
+    Ok(&output[..len])
+}
+
+/// Checks the FCS at the end of a decoded frame and returns the length of the data before it
+fn check_fcs(frame: &[u8], fcs: Fcs) -> Result<usize, HDLCError> {
+    fcs.verify(frame).map(<[u8]>::len)
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -366,15 +1135,120 @@ pub enum HDLCError {
     #[error("Caught a duplicate special character.")]
     DuplicateSpecialChar,
     /// Catches a random sync char in the data.
-    #[error("Caught a random sync char in the data.")]
-    FendCharInData,
+    #[error("Caught a random sync char in the data at byte {offset} of frame {frame}.")]
+    FendCharInData {
+        /// Position of the `fend` in the input
+        offset: usize,
+        /// Index of the frame in the input, always 0 when decoding a single frame
+        frame: usize,
+    },
     /// Catches a random swap char, `fesc`, in the data with no `tfend` or `tfesc`.
-    #[error("Caught a random swap char in the data.")]
-    MissingTradeChar,
+    #[error(
+        "Caught a random swap char in the data, followed by {} at byte {offset} of frame {frame}.",
+        describe_byte(.byte)
+    )]
+    MissingTradeChar {
+        /// Position of the byte after the `fesc` in the input
+        offset: usize,
+        /// The byte after the `fesc`, `None` if the input ended
+        byte: Option<u8>,
+        /// Index of the frame in the input, always 0 when decoding a single frame
+        frame: usize,
+    },
+    /// The sender aborted the frame with `fesc` followed by `fend`.
+    #[error("Frame {frame} was aborted by the sender at byte {offset}.")]
+    Aborted {
+        /// Position of the `fesc` that starts the abort sequence in the input
+        offset: usize,
+        /// Index of the frame in the input, always 0 when decoding a single frame
+        frame: usize,
+    },
+    /// The frame is longer than the maximum frame length.
+    #[error("Frame {frame} is longer than {max} bytes at byte {offset}.")]
+    FrameTooLong {
+        /// Position in the input of the first byte over the limit
+        offset: usize,
+        /// The maximum frame length
+        max: usize,
+        /// Index of the frame in the input, always 0 when decoding a single frame
+        frame: usize,
+    },
+    /// The payload is longer than the maximum frame length, nothing was encoded.
+    #[error("Payload of {len} bytes is longer than the maximum of {max} bytes.")]
+    PayloadTooLong {
+        /// Length of the payload
+        len: usize,
+        /// The maximum frame length
+        max: usize,
+    },
+    /// The frame is shorter than the minimum frame length.
+    #[error("Frame {frame} is a runt of {len} bytes, shorter than {min}, at byte {offset}.")]
+    RuntFrame {
+        /// Position of the closing `fend` in the input
+        offset: usize,
+        /// Length of the decoded frame
+        len: usize,
+        /// The minimum frame length
+        min: usize,
+        /// Index of the frame in the input, always 0 when decoding a single frame
+        frame: usize,
+    },
     /// No first fend on the message.
-    #[error("Missing first FEND character.")]
-    MissingFirstFend,
+    #[error("Missing first FEND character, found {} instead.", describe_byte(.byte))]
+    MissingFirstFend {
+        /// The first byte of the input, `None` if the input is empty
+        byte: Option<u8>,
+    },
     /// No final fend on the message.
-    #[error("Missing final FEND character.")]
-    MissingFinalFend,
+    #[error("Missing final FEND character at byte {offset} of frame {frame}.")]
+    MissingFinalFend {
+        /// Position in the input where the `fend` was expected
+        offset: usize,
+        /// Index of the frame in the input, always 0 when decoding a single frame
+        frame: usize,
+    },
+    /// The output buffer cannot hold the encoded message.
+    #[error("Output buffer is too small for the encoded message.")]
+    BufferTooSmall,
+    /// The frame is too short to hold its FCS.
+    #[error("Frame is too short to hold its FCS.")]
+    MissingFcs,
+    /// The received FCS does not match the one computed over the frame.
+    #[error("FCS mismatch.")]
+    FcsMismatch,
+    /// The frame is too short to hold its address and control fields.
+    #[error("Frame is too short to hold its address and control fields.")]
+    MissingControl,
+    /// The frame is too short to hold its protocol field.
+    #[error("Frame is too short to hold its protocol field.")]
+    MissingProtocol,
+    /// The address field is not one the link uses.
+    #[error("Invalid address {byte:#04X}.")]
+    InvalidAddress {
+        /// The first byte of the address field
+        byte: u8,
+    },
+    /// The control field is not one the link uses.
+    #[error("Invalid control field {byte:#04X}.")]
+    InvalidControl {
+        /// The first byte of the control field
+        byte: u8,
+    },
+    /// A sequence number does not fit the modulus of the link.
+    #[error("Sequence number {value} is out of range modulo {modulus}.")]
+    SequenceOutOfRange {
+        /// The sequence number
+        value: u8,
+        /// Modulus of the sequence numbers
+        modulus: u8,
+    },
+    /// A message carried in a frame is truncated or has an unknown type.
+    #[error("Malformed {protocol} message.")]
+    MalformedMessage {
+        /// Name of the protocol the message belongs to
+        protocol: &'static str,
+    },
+    /// The CRC parameters cannot be used as an FCS.
+    #[error("Invalid CRC parameters.")]
+    InvalidCrcParams,
 }
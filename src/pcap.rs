@@ -0,0 +1,743 @@
+//! # pcap
+//! Decoded frames in and out of pcap and pcapng captures, so traces open in Wireshark.
+//!
+//! [`PcapWriter`] stores each frame, without flags or stuffing, with a timestamp and the
+//! direction it travelled.  [`PcapReader`] reads classic pcap (either byte order, micro or
+//! nanosecond timestamps) and pcapng, and hands the frames back as an iterator of [`Packet`].
+//!
+//! Link types ending in `WithDir` carry the direction in a one byte pseudo-header in front of
+//! the frame.  pcapng also records it in the packet flags, for every link type.  Whether the
+//! FCS is part of the frame is up to the caller, Wireshark has a per protocol setting for it.
+//!
+//! ### Write decoded frames and read them back
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use hdlc::pcap::{Direction, Format, LinkType, PcapReader, PcapWriter};
+//! use hdlc::{decode_frames, SpecialChars};
+//!
+//! let capture = [0x7E, 0xFF, 0x03, 0xC0, 0x21, 0x7E];
+//! let time = Duration::from_secs(1_700_000_000);
+//!
+//! let mut writer = PcapWriter::new(Vec::new(), Format::Pcapng, LinkType::PppHdlc).unwrap();
+//! for frame in decode_frames(&capture, SpecialChars::default()) {
+//!     let frame = frame.unwrap();
+//!     writer.write_frame(&frame, time, Direction::Inbound).unwrap();
+//! }
+//! let file = writer.into_inner();
+//!
+//! let mut reader = PcapReader::new(&file[..]).unwrap();
+//! let packet = reader.next().unwrap().unwrap();
+//!
+//! assert_eq!(packet.data, [0xFF, 0x03, 0xC0, 0x21]);
+//! assert_eq!(packet.link_type, LinkType::PppHdlc);
+//! assert_eq!(packet.direction, Direction::Inbound);
+//! assert_eq!(packet.timestamp, time);
+//! assert!(reader.next().is_none());
+//! ```
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Classic pcap magic for microsecond timestamps
+const PCAP_MAGIC: u32 = 0xA1B2_C3D4;
+/// Classic pcap magic for nanosecond timestamps
+const PCAP_MAGIC_NANOS: u32 = 0xA1B2_3C4D;
+/// pcapng section header block type, also the first four bytes of the file
+const SHB: u32 = 0x0A0D_0D0A;
+/// pcapng interface description block type
+const IDB: u32 = 0x0000_0001;
+/// pcapng simple packet block type
+const SPB: u32 = 0x0000_0003;
+/// pcapng enhanced packet block type
+const EPB: u32 = 0x0000_0006;
+/// pcapng byte order magic
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+/// pcapng `if_tsresol` option
+const IF_TSRESOL: u16 = 9;
+/// pcapng `epb_flags` option
+const EPB_FLAGS: u16 = 2;
+/// Largest frame announced in the file headers
+const SNAPLEN: u32 = 262_144;
+/// Longest record or block read, so a corrupt length cannot make a huge allocation
+const MAX_BLOCK_LEN: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+/// Error for reading and writing captures.
+pub enum Error {
+    /// The underlying reader or writer failed.
+    #[error("Caught an I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// The input does not start with a pcap or pcapng magic number.
+    #[error("Not a pcap or pcapng capture, magic number {0:#010X}.")]
+    BadMagic(u32),
+    /// A header or block does not add up.
+    #[error("Malformed capture: {0}.")]
+    Malformed(&'static str),
+    /// The input ended in the middle of a header or record.
+    #[error("Capture ended in the middle of a record.")]
+    Truncated,
+    /// A timestamp is too far from the Unix epoch for the capture format.
+    #[error("Timestamp {0:?} does not fit in the capture format.")]
+    TimestampOutOfRange(Duration),
+}
+
+/// Capture file format written by a [`PcapWriter`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Classic libpcap format, microsecond timestamps.  Direction only survives in `WithDir`
+    /// link types.
+    #[default]
+    Pcap,
+    /// pcapng, microsecond timestamps and the direction in the packet flags
+    Pcapng,
+}
+
+/// Link-layer header type of the frames in a capture, see
+/// <https://www.tcpdump.org/linktypes.html>.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinkType {
+    /// `LINKTYPE_AX25` (3), AX.25 frames starting with the address field
+    Ax25,
+    /// `LINKTYPE_PPP_HDLC` (50), PPP in HDLC-like framing, RFC 1662
+    PppHdlc,
+    /// `LINKTYPE_C_HDLC` (104), Cisco HDLC
+    CHdlc,
+    /// `LINKTYPE_FRELAY` (107), Frame Relay starting with the Q.922 address
+    FrameRelay,
+    /// `LINKTYPE_AX25_KISS` (202), AX.25 behind a KISS header byte
+    Ax25Kiss,
+    /// `LINKTYPE_LAPD` (203), Q.921 LAPD frames starting with the address field
+    Lapd,
+    /// `LINKTYPE_PPP_WITH_DIR` (204), PPP with a direction pseudo-header
+    PppWithDir,
+    /// `LINKTYPE_C_HDLC_WITH_DIR` (205), Cisco HDLC with a direction pseudo-header
+    CHdlcWithDir,
+    /// `LINKTYPE_FRELAY_WITH_DIR` (206), Frame Relay with a direction pseudo-header
+    FrameRelayWithDir,
+    /// `LINKTYPE_LAPB_WITH_DIR` (207), X.25 LAPB with a direction pseudo-header.  There is no
+    /// LAPB link type without one.
+    LapbWithDir,
+    /// Any other link type, by number
+    Other(u16),
+}
+
+impl LinkType {
+    /// Link type for a `LINKTYPE_` number
+    pub fn from_u16(value: u16) -> LinkType {
+        match value {
+            3 => LinkType::Ax25,
+            50 => LinkType::PppHdlc,
+            104 => LinkType::CHdlc,
+            107 => LinkType::FrameRelay,
+            202 => LinkType::Ax25Kiss,
+            203 => LinkType::Lapd,
+            204 => LinkType::PppWithDir,
+            205 => LinkType::CHdlcWithDir,
+            206 => LinkType::FrameRelayWithDir,
+            207 => LinkType::LapbWithDir,
+            other => LinkType::Other(other),
+        }
+    }
+
+    /// `LINKTYPE_` number stored in the file
+    pub fn to_u16(&self) -> u16 {
+        match *self {
+            LinkType::Ax25 => 3,
+            LinkType::PppHdlc => 50,
+            LinkType::CHdlc => 104,
+            LinkType::FrameRelay => 107,
+            LinkType::Ax25Kiss => 202,
+            LinkType::Lapd => 203,
+            LinkType::PppWithDir => 204,
+            LinkType::CHdlcWithDir => 205,
+            LinkType::FrameRelayWithDir => 206,
+            LinkType::LapbWithDir => 207,
+            LinkType::Other(value) => value,
+        }
+    }
+
+    /// Frames start with a one byte direction pseudo-header
+    pub fn has_direction(&self) -> bool {
+        matches!(
+            self,
+            LinkType::PppWithDir
+                | LinkType::CHdlcWithDir
+                | LinkType::FrameRelayWithDir
+                | LinkType::LapbWithDir
+        )
+    }
+}
+
+/// Which way a frame travelled, seen from the capturing station.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// Not recorded.  Written as received in a `WithDir` pseudo-header.
+    #[default]
+    Unknown,
+    /// Received by the capturing station
+    Inbound,
+    /// Sent by the capturing station
+    Outbound,
+}
+
+impl Direction {
+    /// Value of the `WithDir` pseudo-header
+    fn pseudo_header(self) -> u8 {
+        match self {
+            Direction::Outbound => 1,
+            Direction::Unknown | Direction::Inbound => 0,
+        }
+    }
+
+    /// Bits 0-1 of the pcapng `epb_flags` option
+    fn epb_flags(self) -> u32 {
+        match self {
+            Direction::Unknown => 0,
+            Direction::Inbound => 1,
+            Direction::Outbound => 2,
+        }
+    }
+}
+
+/// A frame read from a capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    /// Capture time since the Unix epoch, zero when the file has none
+    pub timestamp: Duration,
+    /// Direction from the pseudo-header or the packet flags
+    pub direction: Direction,
+    /// Link type of the interface the frame was captured on
+    pub link_type: LinkType,
+    /// The frame, without the direction pseudo-header
+    pub data: Vec<u8>,
+}
+
+/// Writes decoded frames to a pcap or pcapng capture.
+#[derive(Debug)]
+pub struct PcapWriter<W> {
+    inner: W,
+    format: Format,
+    link_type: LinkType,
+}
+
+impl<W: Write> PcapWriter<W> {
+    /// Writes the file header, plus the interface description for pcapng
+    ///
+    /// # Error
+    ///
+    /// * **Error::Io**: The writer failed.
+    pub fn new(mut inner: W, format: Format, link_type: LinkType) -> Result<Self, Error> {
+        let mut header = Vec::with_capacity(48);
+        match format {
+            Format::Pcap => {
+                header.extend_from_slice(&PCAP_MAGIC.to_le_bytes());
+                header.extend_from_slice(&2u16.to_le_bytes());
+                header.extend_from_slice(&4u16.to_le_bytes());
+                // Time zone offset and timestamp accuracy, both always 0
+                header.extend_from_slice(&[0; 8]);
+                header.extend_from_slice(&SNAPLEN.to_le_bytes());
+                header.extend_from_slice(&u32::from(link_type.to_u16()).to_le_bytes());
+            }
+            Format::Pcapng => {
+                header.extend_from_slice(&SHB.to_le_bytes());
+                header.extend_from_slice(&28u32.to_le_bytes());
+                header.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+                header.extend_from_slice(&1u16.to_le_bytes());
+                header.extend_from_slice(&0u16.to_le_bytes());
+                // Section length not known up front
+                header.extend_from_slice(&(-1i64).to_le_bytes());
+                header.extend_from_slice(&28u32.to_le_bytes());
+
+                header.extend_from_slice(&IDB.to_le_bytes());
+                header.extend_from_slice(&20u32.to_le_bytes());
+                header.extend_from_slice(&link_type.to_u16().to_le_bytes());
+                header.extend_from_slice(&0u16.to_le_bytes());
+                header.extend_from_slice(&SNAPLEN.to_le_bytes());
+                header.extend_from_slice(&20u32.to_le_bytes());
+            }
+        }
+        inner.write_all(&header)?;
+
+        Ok(PcapWriter {
+            inner,
+            format,
+            link_type,
+        })
+    }
+
+    /// Writes one decoded frame, captured `timestamp` after the Unix epoch
+    ///
+    /// # Error
+    ///
+    /// * **Error::Io**: The writer failed.
+    /// * **Error::TimestampOutOfRange**: `timestamp` is past 2106 in a pcap file, whose
+    ///   seconds are 32 bits.
+    pub fn write_frame(
+        &mut self,
+        frame: &[u8],
+        timestamp: Duration,
+        direction: Direction,
+    ) -> Result<(), Error> {
+        let mut data = Vec::with_capacity(frame.len() + 1);
+        if self.link_type.has_direction() {
+            data.push(direction.pseudo_header());
+        }
+        data.extend_from_slice(frame);
+        let len = data.len() as u32;
+
+        let mut record = Vec::with_capacity(data.len() + 48);
+        match self.format {
+            Format::Pcap => {
+                let secs = u32::try_from(timestamp.as_secs())
+                    .map_err(|_| Error::TimestampOutOfRange(timestamp))?;
+
+                record.extend_from_slice(&secs.to_le_bytes());
+                record.extend_from_slice(&timestamp.subsec_micros().to_le_bytes());
+                record.extend_from_slice(&len.to_le_bytes());
+                record.extend_from_slice(&len.to_le_bytes());
+                record.extend_from_slice(&data);
+            }
+            Format::Pcapng => {
+                let padding = (4 - data.len() % 4) % 4;
+                // epb_flags and the end of options
+                let options = if direction == Direction::Unknown {
+                    0
+                } else {
+                    12
+                };
+                let total = (32 + data.len() + padding + options) as u32;
+                let micros = u64::try_from(timestamp.as_micros())
+                    .map_err(|_| Error::TimestampOutOfRange(timestamp))?;
+
+                record.extend_from_slice(&EPB.to_le_bytes());
+                record.extend_from_slice(&total.to_le_bytes());
+                record.extend_from_slice(&0u32.to_le_bytes());
+                record.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+                record.extend_from_slice(&(micros as u32).to_le_bytes());
+                record.extend_from_slice(&len.to_le_bytes());
+                record.extend_from_slice(&len.to_le_bytes());
+                record.extend_from_slice(&data);
+                record.extend_from_slice(&[0; 3][..padding]);
+                if direction != Direction::Unknown {
+                    record.extend_from_slice(&EPB_FLAGS.to_le_bytes());
+                    record.extend_from_slice(&4u16.to_le_bytes());
+                    record.extend_from_slice(&direction.epb_flags().to_le_bytes());
+                    record.extend_from_slice(&[0; 4]);
+                }
+                record.extend_from_slice(&total.to_le_bytes());
+            }
+        }
+        self.inner.write_all(&record)?;
+
+        Ok(())
+    }
+
+    /// Flushes the writer and hands it back
+    ///
+    /// # Error
+    ///
+    /// * **Error::Io**: The flush failed.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+
+    /// Hands back the writer without flushing it
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Interface described in a pcapng section
+#[derive(Debug, Copy, Clone)]
+struct Interface {
+    link_type: LinkType,
+    // Timestamp units per second
+    resolution: u64,
+}
+
+#[derive(Debug)]
+enum Layout {
+    Pcap { link_type: LinkType, nanos: bool },
+    Pcapng { interfaces: Vec<Interface> },
+}
+
+/// Reads the frames of a pcap or pcapng capture.
+///
+/// Iterates over the packets in the file.  Other pcapng blocks are skipped, and the iterator
+/// stops after the first error.
+#[derive(Debug)]
+pub struct PcapReader<R> {
+    inner: R,
+    layout: Layout,
+    big_endian: bool,
+    done: bool,
+}
+
+impl<R: Read> PcapReader<R> {
+    /// Reads the file header and detects the format and byte order
+    ///
+    /// # Error
+    ///
+    /// * **Error::Io**: The reader failed.
+    /// * **Error::BadMagic**: The input is neither pcap nor pcapng.
+    /// * **Error::Malformed**: The pcapng section header does not add up.
+    /// * **Error::Truncated**: The input ended inside the file header.
+    pub fn new(mut inner: R) -> Result<Self, Error> {
+        let mut magic = [0u8; 4];
+        read_exact(&mut inner, &mut magic)?;
+
+        let mut reader = match u32::from_le_bytes(magic) {
+            SHB => {
+                let mut reader = PcapReader {
+                    inner,
+                    layout: Layout::Pcapng {
+                        interfaces: Vec::new(),
+                    },
+                    big_endian: false,
+                    done: false,
+                };
+                let mut len = [0u8; 4];
+                read_exact(&mut reader.inner, &mut len)?;
+                reader.read_section_header(len)?;
+                return Ok(reader);
+            }
+            magic => {
+                let (big_endian, nanos) = match magic {
+                    PCAP_MAGIC => (false, false),
+                    PCAP_MAGIC_NANOS => (false, true),
+                    m if m.swap_bytes() == PCAP_MAGIC => (true, false),
+                    m if m.swap_bytes() == PCAP_MAGIC_NANOS => (true, true),
+                    m => return Err(Error::BadMagic(m.swap_bytes())),
+                };
+                PcapReader {
+                    inner,
+                    layout: Layout::Pcap {
+                        link_type: LinkType::Other(0),
+                        nanos,
+                    },
+                    big_endian,
+                    done: false,
+                }
+            }
+        };
+
+        // Version, time zone, accuracy, snaplen and link type
+        let mut header = [0u8; 20];
+        read_exact(&mut reader.inner, &mut header)?;
+        let network = reader.u32_at(&header, 16);
+        if let Layout::Pcap { link_type, .. } = &mut reader.layout {
+            // The upper bits may carry FCS length flags
+            *link_type = LinkType::from_u16(network as u16);
+        }
+
+        Ok(reader)
+    }
+
+    /// Link type of a classic pcap file, or of the first pcapng interface seen so far
+    pub fn link_type(&self) -> Option<LinkType> {
+        match &self.layout {
+            Layout::Pcap { link_type, .. } => Some(*link_type),
+            Layout::Pcapng { interfaces } => interfaces.first().map(|i| i.link_type),
+        }
+    }
+
+    /// Hands back the reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads the rest of a section header block, after its type and raw length
+    fn read_section_header(&mut self, len: [u8; 4]) -> Result<(), Error> {
+        let mut magic = [0u8; 4];
+        read_exact(&mut self.inner, &mut magic)?;
+        self.big_endian = match u32::from_le_bytes(magic) {
+            BYTE_ORDER_MAGIC => false,
+            m if m.swap_bytes() == BYTE_ORDER_MAGIC => true,
+            _ => return Err(Error::Malformed("bad pcapng byte order magic")),
+        };
+        let len = self.u32_at(&len, 0) as usize;
+        if !(28..=MAX_BLOCK_LEN).contains(&len) || !len.is_multiple_of(4) {
+            return Err(Error::Malformed("bad section header length"));
+        }
+        // Versions, section length and options are not needed
+        let mut rest = vec![0u8; len - 12];
+        read_exact(&mut self.inner, &mut rest)?;
+        if let Layout::Pcapng { interfaces } = &mut self.layout {
+            interfaces.clear();
+        }
+
+        Ok(())
+    }
+
+    fn read_pcap_record(
+        &mut self,
+        link_type: LinkType,
+        nanos: bool,
+    ) -> Option<Result<Packet, Error>> {
+        let mut header = [0u8; 16];
+        match read_record_start(&mut self.inner, &mut header) {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(e) => return Some(Err(e)),
+        }
+        let secs = self.u32_at(&header, 0);
+        let frac = self.u32_at(&header, 4);
+        let len = self.u32_at(&header, 8) as usize;
+        if len > MAX_BLOCK_LEN {
+            return Some(Err(Error::Malformed("bad record length")));
+        }
+
+        let mut data = vec![0u8; len];
+        if let Err(e) = read_exact(&mut self.inner, &mut data) {
+            return Some(Err(e));
+        }
+        let nanos = if nanos {
+            frac
+        } else {
+            frac.saturating_mul(1000)
+        };
+        let timestamp =
+            Duration::from_secs(u64::from(secs)) + Duration::from_nanos(u64::from(nanos));
+
+        Some(Ok(packet(&data, timestamp, Direction::Unknown, link_type)))
+    }
+
+    fn read_pcapng_block(&mut self) -> Option<Result<Packet, Error>> {
+        loop {
+            let mut head = [0u8; 8];
+            match read_record_start(&mut self.inner, &mut head) {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+            let block_type = self.u32_at(&head, 0);
+            if block_type == SHB {
+                // A new section, possibly in the other byte order
+                let len = [head[4], head[5], head[6], head[7]];
+                if let Err(e) = self.read_section_header(len) {
+                    return Some(Err(e));
+                }
+                continue;
+            }
+
+            let len = self.u32_at(&head, 4) as usize;
+            if !(12..=MAX_BLOCK_LEN).contains(&len) || !len.is_multiple_of(4) {
+                return Some(Err(Error::Malformed("bad block length")));
+            }
+            let mut body = vec![0u8; len - 8];
+            if let Err(e) = read_exact(&mut self.inner, &mut body) {
+                return Some(Err(e));
+            }
+            // The trailing copy of the length is not part of the body
+            body.truncate(len - 12);
+
+            let result = match block_type {
+                IDB => self.read_interface(&body).map(|()| None),
+                EPB => self.read_enhanced_packet(&body).map(Some),
+                SPB => self.read_simple_packet(&body).map(Some),
+                _ => Ok(None),
+            };
+            match result {
+                Ok(None) => continue,
+                Ok(Some(packet)) => return Some(Ok(packet)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    fn read_interface(&mut self, body: &[u8]) -> Result<(), Error> {
+        if body.len() < 8 {
+            return Err(Error::Malformed("short interface description"));
+        }
+        let link_type = LinkType::from_u16(self.u16_at(body, 0));
+        let mut resolution = 1_000_000;
+        for (code, value) in self.options(&body[8..])? {
+            if code == IF_TSRESOL {
+                let exponent = *value
+                    .first()
+                    .ok_or(Error::Malformed("empty if_tsresol option"))?;
+                let power = if exponent & 0x80 != 0 {
+                    2u64.checked_pow(u32::from(exponent & 0x7F))
+                } else {
+                    10u64.checked_pow(u32::from(exponent))
+                };
+                resolution = power.ok_or(Error::Malformed("if_tsresol out of range"))?;
+            }
+        }
+
+        if let Layout::Pcapng { interfaces } = &mut self.layout {
+            interfaces.push(Interface {
+                link_type,
+                resolution,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn read_enhanced_packet(&self, body: &[u8]) -> Result<Packet, Error> {
+        if body.len() < 20 {
+            return Err(Error::Malformed("short enhanced packet block"));
+        }
+        let interface = self.interface(self.u32_at(body, 0))?;
+        let ticks = (u64::from(self.u32_at(body, 4)) << 32) | u64::from(self.u32_at(body, 8));
+        let len = self.u32_at(body, 12) as usize;
+        let end = 20 + len;
+        let data = body
+            .get(20..end)
+            .ok_or(Error::Malformed("packet longer than its block"))?;
+
+        let mut direction = Direction::Unknown;
+        let options_start = (end + 3) & !3;
+        for (code, value) in self.options(body.get(options_start..).unwrap_or(&[]))? {
+            if code == EPB_FLAGS && value.len() == 4 {
+                direction = match self.u32_at(value, 0) & 0x03 {
+                    1 => Direction::Inbound,
+                    2 => Direction::Outbound,
+                    _ => Direction::Unknown,
+                };
+            }
+        }
+        let units = u128::from(interface.resolution);
+        let nanos = u128::from(ticks) * 1_000_000_000 / units;
+        let timestamp = Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        );
+
+        Ok(packet(data, timestamp, direction, interface.link_type))
+    }
+
+    fn read_simple_packet(&self, body: &[u8]) -> Result<Packet, Error> {
+        if body.len() < 4 {
+            return Err(Error::Malformed("short simple packet block"));
+        }
+        let interface = self.interface(0)?;
+        // Captured length is whatever fits in the block
+        let len = (self.u32_at(body, 0) as usize).min(body.len() - 4);
+
+        Ok(packet(
+            &body[4..4 + len],
+            Duration::ZERO,
+            Direction::Unknown,
+            interface.link_type,
+        ))
+    }
+
+    fn interface(&self, id: u32) -> Result<Interface, Error> {
+        match &self.layout {
+            Layout::Pcapng { interfaces } => interfaces.get(id as usize).copied(),
+            Layout::Pcap { .. } => None,
+        }
+        .ok_or(Error::Malformed("packet for an undescribed interface"))
+    }
+
+    /// Splits a pcapng option list into `(code, value)` pairs
+    fn options<'b>(&self, mut bytes: &'b [u8]) -> Result<Vec<(u16, &'b [u8])>, Error> {
+        let mut options = Vec::new();
+        while bytes.len() >= 4 {
+            let code = self.u16_at(bytes, 0);
+            let len = usize::from(self.u16_at(bytes, 2));
+            if code == 0 {
+                break;
+            }
+            let padded = (len + 3) & !3;
+            let value = bytes
+                .get(4..4 + len)
+                .ok_or(Error::Malformed("option longer than its block"))?;
+            options.push((code, value));
+            bytes = bytes.get(4 + padded..).unwrap_or(&[]);
+        }
+
+        Ok(options)
+    }
+
+    fn u16_at(&self, bytes: &[u8], at: usize) -> u16 {
+        let raw = [bytes[at], bytes[at + 1]];
+        if self.big_endian {
+            u16::from_be_bytes(raw)
+        } else {
+            u16::from_le_bytes(raw)
+        }
+    }
+
+    fn u32_at(&self, bytes: &[u8], at: usize) -> u32 {
+        let raw = [bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]];
+        if self.big_endian {
+            u32::from_be_bytes(raw)
+        } else {
+            u32::from_le_bytes(raw)
+        }
+    }
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+    type Item = Result<Packet, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = match self.layout {
+            Layout::Pcap { link_type, nanos } => self.read_pcap_record(link_type, nanos),
+            Layout::Pcapng { .. } => self.read_pcapng_block(),
+        };
+        if !matches!(next, Some(Ok(_))) {
+            self.done = true;
+        }
+
+        next
+    }
+}
+
+/// Builds a packet, taking the direction from the pseudo-header of `WithDir` link types
+fn packet(data: &[u8], timestamp: Duration, direction: Direction, link_type: LinkType) -> Packet {
+    let mut data = data;
+    let mut direction = direction;
+    if let Some((header, rest)) = data.split_first().filter(|_| link_type.has_direction()) {
+        data = rest;
+        if direction == Direction::Unknown {
+            direction = match header {
+                0 => Direction::Inbound,
+                1 => Direction::Outbound,
+                _ => Direction::Unknown,
+            };
+        }
+    }
+
+    Packet {
+        timestamp,
+        direction,
+        link_type,
+        data: data.to_vec(),
+    }
+}
+
+/// Fills `buf`, end of input is a truncated capture
+fn read_exact(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), Error> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => Error::Truncated,
+        _ => Error::Io(e),
+    })
+}
+
+/// Fills `buf` with the start of a record.  Returns `false` at a clean end of input.
+fn read_record_start(reader: &mut impl Read, buf: &mut [u8]) -> Result<bool, Error> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(Error::Truncated),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(Error::Io(e)),
+        }
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,460 @@
+//! # serial
+//! Frame-level reading and writing over [`embedded-io`](https://docs.rs/embedded-io) byte
+//! streams, such as the UARTs exposed by `embedded-hal` 1.0 HALs.
+//!
+//! Frames are received into a caller supplied buffer and decoded in place with
+//! [`decode_slice`](crate::decode_slice), and are stuffed straight onto the writer, so neither
+//! direction allocates.  The async variants for `embedded-io-async` live in [`asynch`].
+//!
+//! None of this needs `std`, so firmware can depend on the crate with
+//! `default-features = false, features = ["embedded-io"]`.
+//!
+//! ### Send and receive a frame
+//! ```rust
+//! use hdlc::serial::{FrameReader, FrameWriter};
+//! use hdlc::SpecialChars;
+//!
+//! let mut wire = [0u8; 32];
+//! let mut writer = FrameWriter::new(&mut wire[..], SpecialChars::default());
+//! writer.write_frame(&[0x01, 0x7E, 0x02]).unwrap();
+//!
+//! let mut reader = FrameReader::new(&wire[..], SpecialChars::default());
+//! let mut buf = [0u8; 16];
+//! let frame = reader.read_frame(&mut buf).unwrap();
+//!
+//! assert_eq!(frame, [0x01, 0x7E, 0x02]);
+//! ```
+
+use crate::{
+    abort_sequence, decode_slice_with_options, find_special, DecodeOptions, HDLCError, OpeningFend,
+    RuntPolicy, SpecialChars,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+/// Error for frame reads and writes on an `embedded-io` stream.
+pub enum Error<E> {
+    /// The underlying reader or writer failed.
+    #[error("Caught an I/O error: {0:?}")]
+    Io(E),
+    /// The frame was read but could not be decoded.
+    #[error(transparent)]
+    Hdlc(#[from] HDLCError),
+    /// The frame did not fit in the supplied buffer.
+    #[error("Frame overflowed the receive buffer.")]
+    Overflow,
+    /// The reader hit end of stream before the closing `fend`.
+    #[error("Unexpected end of stream.")]
+    UnexpectedEof,
+}
+
+impl<E> From<embedded_io::ReadExactError<E>> for Error<E> {
+    fn from(err: embedded_io::ReadExactError<E>) -> Self {
+        match err {
+            embedded_io::ReadExactError::UnexpectedEof => Error::UnexpectedEof,
+            embedded_io::ReadExactError::Other(e) => Error::Io(e),
+        }
+    }
+}
+
+/// Result of storing one received byte of a raw frame
+enum Step {
+    /// Keep reading bytes
+    Continue,
+    /// The closing `fend` was stored, the raw frame is `buf[..len]`
+    Done(usize),
+    /// The raw frame does not fit in the buffer
+    Overflow,
+}
+
+/// Stores `byte` at `buf[*len]`, where `buf[0]` already holds the opening `fend`
+fn push_byte(buf: &mut [u8], len: &mut usize, byte: u8, s_chars: SpecialChars) -> Step {
+    // Back to back flags are inter-frame fill, keep the last one as the opening flag
+    if byte == s_chars.fend && *len == 1 {
+        return Step::Continue;
+    }
+    match buf.get_mut(*len) {
+        Some(slot) => *slot = byte,
+        None => return Step::Overflow,
+    }
+    *len += 1;
+
+    if byte == s_chars.fend {
+        Step::Done(*len)
+    } else {
+        Step::Continue
+    }
+}
+
+/// Error for the garbage starting with `first` that was skipped to reach the first `fend`, if
+/// it is reported
+fn missing_first_fend(first: u8, started: bool, options: DecodeOptions) -> Option<HDLCError> {
+    match options.opening_fend {
+        OpeningFend::Required if !started => {
+            Some(HDLCError::MissingFirstFend { byte: Some(first) })
+        }
+        _ => None,
+    }
+}
+
+/// Decodes the raw frame in `buf` in place and returns its length, or `None` for a runt to skip
+fn decode_raw(
+    buf: &mut [u8],
+    s_chars: SpecialChars,
+    options: DecodeOptions,
+) -> Option<Result<usize, HDLCError>> {
+    match decode_slice_with_options(buf, s_chars, options) {
+        Ok(frame) => Some(Ok(frame.len())),
+        Err(HDLCError::RuntFrame { .. }) if options.runts == RuntPolicy::Skip => None,
+        Err(err) => Some(Err(err)),
+    }
+}
+
+/// Fails if `data` is longer than the `max_frame_len` of a writer
+fn check_len(data: &[u8], max_frame_len: Option<usize>) -> Result<(), HDLCError> {
+    match max_frame_len {
+        Some(max) if data.len() > max => Err(HDLCError::PayloadTooLong {
+            len: data.len(),
+            max,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Splits off the leading run of plain bytes and, if one follows, the escape pair for the
+/// special byte after it.  Returns `(run, escape, rest)`.
+fn split_run(data: &[u8], s_chars: SpecialChars) -> (&[u8], Option<[u8; 2]>, &[u8]) {
+    let run = find_special(s_chars, data).unwrap_or(data.len());
+    let (head, tail) = data.split_at(run);
+
+    match tail.split_first() {
+        Some((&b, rest)) if b == s_chars.fend => (head, Some([s_chars.fesc, s_chars.tfend]), rest),
+        Some((_, rest)) => (head, Some([s_chars.fesc, s_chars.tfesc]), rest),
+        None => (head, None, tail),
+    }
+}
+
+/// Reads whole HDLC frames from an [`embedded_io::Read`] stream.
+///
+/// Bytes before the first `fend` fail the first read with [`HDLCError::MissingFirstFend`], or
+/// are dropped under [`OpeningFend::Hunt`].  A closing `fend` may also open the next frame, so
+/// peers that share flags between frames are handled.
+pub struct FrameReader<R> {
+    inner: R,
+    s_chars: SpecialChars,
+    options: DecodeOptions,
+    synced: bool,
+    // The first FEND was found, later hunts skip what is left of a bad frame
+    started: bool,
+}
+
+impl<R: embedded_io::Read> FrameReader<R> {
+    /// Creates a new FrameReader over `inner` using the given special characters
+    pub fn new(inner: R, s_chars: SpecialChars) -> FrameReader<R> {
+        FrameReader {
+            inner,
+            s_chars,
+            options: DecodeOptions::default(),
+            synced: false,
+            started: false,
+        }
+    }
+
+    /// Sets limits on the frames accepted, see [`DecodeOptions`].  With
+    /// [`OpeningFend::Optional`], the first frame starts with the first byte read.
+    pub fn with_options(mut self, options: DecodeOptions) -> FrameReader<R> {
+        self.synced |= options.opening_fend == OpeningFend::Optional;
+        self.started |= self.synced;
+        self.options = options;
+        self
+    }
+
+    /// Reads the next frame into `buf` and returns the decoded payload, which borrows `buf`
+    ///
+    /// # Error
+    ///
+    /// * **Error::Io**: The reader failed.
+    /// * **Error::UnexpectedEof**: The stream ended before the closing `fend`.
+    /// * **Error::Overflow**: The raw frame, flags included, is longer than `buf`.  The rest of
+    ///   the frame is skipped on the next read.
+    /// * **Error::Hdlc**: The frame failed to decode, see
+    ///   [`decode_slice_with_options`].  Offsets count from the opening `fend`.  Runts are
+    ///   skipped instead under [`RuntPolicy::Skip`].  Bytes before the first `fend` fail with
+    ///   `MissingFirstFend` under [`OpeningFend::Required`], the next read starts after them.
+    pub fn read_frame<'b>(&mut self, buf: &'b mut [u8]) -> Result<&'b [u8], Error<R::Error>> {
+        // Hunt for the opening FEND unless the last frame closed cleanly
+        if !self.synced {
+            let first = self.read_byte()?;
+            if first != self.s_chars.fend {
+                while self.read_byte()? != self.s_chars.fend {}
+                if let Some(error) = missing_first_fend(first, self.started, self.options) {
+                    self.synced = true;
+                    self.started = true;
+                    return Err(error.into());
+                }
+            }
+        }
+        self.synced = false;
+        self.started = true;
+
+        match buf.first_mut() {
+            Some(first) => *first = self.s_chars.fend,
+            None => return Err(Error::Overflow),
+        }
+        let mut len = 1;
+        loop {
+            let byte = self.read_byte()?;
+            match push_byte(buf, &mut len, byte, self.s_chars) {
+                Step::Continue => {}
+                Step::Done(end) => {
+                    self.synced = true;
+                    match decode_raw(&mut buf[..end], self.s_chars, self.options) {
+                        Some(decoded) => return Ok(&buf[..decoded?]),
+                        // A skipped runt is fill, its closing FEND opens the next frame
+                        None => {
+                            buf[0] = self.s_chars.fend;
+                            len = 1;
+                        }
+                    }
+                }
+                Step::Overflow => return Err(Error::Overflow),
+            }
+        }
+    }
+
+    /// Consumes the FrameReader and returns the underlying reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Error<R::Error>> {
+        let mut byte = [0u8; 1];
+        self.inner.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+}
+
+/// Writes whole HDLC frames to an [`embedded_io::Write`] stream.
+pub struct FrameWriter<W> {
+    inner: W,
+    s_chars: SpecialChars,
+    max_frame_len: Option<usize>,
+}
+
+impl<W: embedded_io::Write> FrameWriter<W> {
+    /// Creates a new FrameWriter over `inner` using the given special characters
+    pub fn new(inner: W, s_chars: SpecialChars) -> FrameWriter<W> {
+        FrameWriter {
+            inner,
+            s_chars,
+            max_frame_len: None,
+        }
+    }
+
+    /// Sets the longest payload written in bytes (N1), no limit by default
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> FrameWriter<W> {
+        self.max_frame_len = Some(max_frame_len);
+        self
+    }
+
+    /// Escapes `data`, writes it surrounded with `FEND` and flushes the writer
+    ///
+    /// # Error
+    ///
+    /// * **Error::Hdlc(HDLCError::PayloadTooLong)**: `data` is longer than the
+    ///   `max_frame_len` set.  Nothing is written.
+    /// * **Error::Io**: The writer failed.
+    pub fn write_frame(&mut self, data: &[u8]) -> Result<(), Error<W::Error>> {
+        check_len(data, self.max_frame_len)?;
+        let s_chars = self.s_chars;
+        let mut rest = data;
+
+        self.inner.write_all(&[s_chars.fend]).map_err(Error::Io)?;
+        while !rest.is_empty() {
+            let (run, escape, tail) = split_run(rest, s_chars);
+            self.inner.write_all(run).map_err(Error::Io)?;
+            if let Some(pair) = escape {
+                self.inner.write_all(&pair).map_err(Error::Io)?;
+            }
+            rest = tail;
+        }
+        self.inner.write_all(&[s_chars.fend]).map_err(Error::Io)?;
+        self.inner.flush().map_err(Error::Io)
+    }
+
+    /// Writes the abort sequence and flushes the writer
+    ///
+    /// The receiver drops the frame in progress, see [`abort_sequence`].
+    pub fn write_abort(&mut self) -> Result<(), Error<W::Error>> {
+        self.inner
+            .write_all(&abort_sequence(self.s_chars))
+            .map_err(Error::Io)?;
+        self.inner.flush().map_err(Error::Io)
+    }
+
+    /// Consumes the FrameWriter and returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Async frame reading and writing over `embedded-io-async` streams.
+#[cfg(feature = "embedded-io-async")]
+pub mod asynch {
+    use super::{check_len, decode_raw, missing_first_fend, push_byte, split_run, Error, Step};
+    use crate::{abort_sequence, DecodeOptions, OpeningFend, SpecialChars};
+
+    /// Async version of [`super::FrameReader`].
+    pub struct FrameReader<R> {
+        inner: R,
+        s_chars: SpecialChars,
+        options: DecodeOptions,
+        synced: bool,
+        started: bool,
+    }
+
+    impl<R: embedded_io_async::Read> FrameReader<R> {
+        /// Creates a new FrameReader over `inner` using the given special characters
+        pub fn new(inner: R, s_chars: SpecialChars) -> FrameReader<R> {
+            FrameReader {
+                inner,
+                s_chars,
+                options: DecodeOptions::default(),
+                synced: false,
+                started: false,
+            }
+        }
+
+        /// Sets limits on the frames accepted, see [`DecodeOptions`].  With
+        /// [`OpeningFend::Optional`], the first frame starts with the first byte read.
+        pub fn with_options(mut self, options: DecodeOptions) -> FrameReader<R> {
+            self.synced |= options.opening_fend == OpeningFend::Optional;
+            self.started |= self.synced;
+            self.options = options;
+            self
+        }
+
+        /// Reads the next frame into `buf` and returns the decoded payload, which borrows `buf`
+        ///
+        /// Errors are the same as [`super::FrameReader::read_frame`].
+        pub async fn read_frame<'b>(
+            &mut self,
+            buf: &'b mut [u8],
+        ) -> Result<&'b [u8], Error<R::Error>> {
+            // Hunt for the opening FEND unless the last frame closed cleanly
+            if !self.synced {
+                let first = self.read_byte().await?;
+                if first != self.s_chars.fend {
+                    while self.read_byte().await? != self.s_chars.fend {}
+                    if let Some(error) = missing_first_fend(first, self.started, self.options) {
+                        self.synced = true;
+                        self.started = true;
+                        return Err(error.into());
+                    }
+                }
+            }
+            self.synced = false;
+            self.started = true;
+
+            match buf.first_mut() {
+                Some(first) => *first = self.s_chars.fend,
+                None => return Err(Error::Overflow),
+            }
+            let mut len = 1;
+            loop {
+                let byte = self.read_byte().await?;
+                match push_byte(buf, &mut len, byte, self.s_chars) {
+                    Step::Continue => {}
+                    Step::Done(end) => {
+                        self.synced = true;
+                        match decode_raw(&mut buf[..end], self.s_chars, self.options) {
+                            Some(decoded) => return Ok(&buf[..decoded?]),
+                            // A skipped runt is fill, its closing FEND opens the next frame
+                            None => {
+                                buf[0] = self.s_chars.fend;
+                                len = 1;
+                            }
+                        }
+                    }
+                    Step::Overflow => return Err(Error::Overflow),
+                }
+            }
+        }
+
+        /// Consumes the FrameReader and returns the underlying reader
+        pub fn into_inner(self) -> R {
+            self.inner
+        }
+
+        async fn read_byte(&mut self) -> Result<u8, Error<R::Error>> {
+            let mut byte = [0u8; 1];
+            self.inner.read_exact(&mut byte).await?;
+            Ok(byte[0])
+        }
+    }
+
+    /// Async version of [`super::FrameWriter`].
+    pub struct FrameWriter<W> {
+        inner: W,
+        s_chars: SpecialChars,
+        max_frame_len: Option<usize>,
+    }
+
+    impl<W: embedded_io_async::Write> FrameWriter<W> {
+        /// Creates a new FrameWriter over `inner` using the given special characters
+        pub fn new(inner: W, s_chars: SpecialChars) -> FrameWriter<W> {
+            FrameWriter {
+                inner,
+                s_chars,
+                max_frame_len: None,
+            }
+        }
+
+        /// Sets the longest payload written in bytes (N1), no limit by default
+        pub fn with_max_frame_len(mut self, max_frame_len: usize) -> FrameWriter<W> {
+            self.max_frame_len = Some(max_frame_len);
+            self
+        }
+
+        /// Escapes `data`, writes it surrounded with `FEND` and flushes the writer
+        ///
+        /// Errors are the same as [`super::FrameWriter::write_frame`].
+        pub async fn write_frame(&mut self, data: &[u8]) -> Result<(), Error<W::Error>> {
+            check_len(data, self.max_frame_len)?;
+            let s_chars = self.s_chars;
+            let mut rest = data;
+
+            self.inner
+                .write_all(&[s_chars.fend])
+                .await
+                .map_err(Error::Io)?;
+            while !rest.is_empty() {
+                let (run, escape, tail) = split_run(rest, s_chars);
+                self.inner.write_all(run).await.map_err(Error::Io)?;
+                if let Some(pair) = escape {
+                    self.inner.write_all(&pair).await.map_err(Error::Io)?;
+                }
+                rest = tail;
+            }
+            self.inner
+                .write_all(&[s_chars.fend])
+                .await
+                .map_err(Error::Io)?;
+            self.inner.flush().await.map_err(Error::Io)
+        }
+
+        /// Writes the abort sequence and flushes the writer
+        pub async fn write_abort(&mut self) -> Result<(), Error<W::Error>> {
+            self.inner
+                .write_all(&abort_sequence(self.s_chars))
+                .await
+                .map_err(Error::Io)?;
+            self.inner.flush().await.map_err(Error::Io)
+        }
+
+        /// Consumes the FrameWriter and returns the underlying writer
+        pub fn into_inner(self) -> W {
+            self.inner
+        }
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::ax25::{Callsign, CommandResponse, Digipeater, Frame, PID_NO_LAYER3};
+    use hdlc::frame::{Control, Supervisory};
+    use hdlc::{decode, encode, kiss, HDLCError, SpecialChars};
+
+    const APRS_VIA_WIDE: [u8; 31] = [
+        0x82, 0xA0, 0xA4, 0xA6, 0x40, 0x40, 0xE0, 0x9C, 0x60, 0x86, 0x82, 0x98, 0x98, 0x6E, 0xAE,
+        0x92, 0x88, 0x8A, 0x62, 0x40, 0xE3, 0x03, 0xF0, b'!', b'4', b'9', b'0', b'3', b'.', b'5',
+        b'0',
+    ];
+
+    fn call(text: &str) -> Callsign {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_ui_frame_with_digipeater() {
+        let result = Frame::parse(&APRS_VIA_WIDE);
+
+        assert!(result.is_ok());
+        let frame = result.unwrap();
+        assert_eq!(frame.destination, call("APRS"));
+        assert_eq!(frame.source, call("N0CALL-7"));
+        assert_eq!(
+            frame.digipeaters,
+            [Digipeater {
+                callsign: call("WIDE1-1"),
+                repeated: true
+            }]
+        );
+        assert_eq!(frame.kind, CommandResponse::Command);
+        assert_eq!(frame.pid, Some(PID_NO_LAYER3));
+        assert_eq!(frame.info, b"!4903.50");
+        assert_eq!(frame.to_bytes(), APRS_VIA_WIDE)
+    }
+
+    #[test]
+    fn builds_supervisory_response() {
+        let frame = Frame {
+            destination: call("n0call"),
+            source: call("W1AW-15"),
+            digipeaters: Vec::new(),
+            kind: CommandResponse::Response,
+            control: Control::Supervisory {
+                kind: Supervisory::Rr,
+                nr: 3,
+                pf: true,
+            },
+            pid: None,
+            info: &[],
+        };
+
+        let bytes = frame.to_bytes();
+        let result = Frame::parse(&bytes);
+
+        assert_eq!(bytes.len(), 15);
+        assert_eq!(bytes[6], 0x60);
+        assert_eq!(bytes[13], 0xFF);
+        assert_eq!(result.unwrap(), frame)
+    }
+
+    #[test]
+    fn travels_inside_kiss() {
+        let ax25 = Frame::ui(call("CQ"), call("N0CALL"), &[0xC0]).to_bytes();
+        let data = kiss::Frame {
+            port: 0,
+            command: kiss::Command::Data(&ax25),
+        };
+
+        let wire = encode(&data.to_bytes(), SpecialChars::KISS).unwrap();
+        let raw = decode(&wire, SpecialChars::KISS).unwrap();
+        let result = match kiss::Frame::parse(&raw).unwrap().command {
+            kiss::Command::Data(bytes) => Frame::parse(bytes).unwrap().info.to_vec(),
+            _ => Vec::new(),
+        };
+
+        assert_eq!(result, [0xC0])
+    }
+
+    #[test]
+    fn rejects_bad_callsigns() {
+        let malformed = HDLCError::MalformedMessage {
+            protocol: "AX.25 callsign",
+        };
+
+        assert_eq!("TOOLONG".parse::<Callsign>().unwrap_err(), malformed);
+        assert_eq!("N0CALL-16".parse::<Callsign>().unwrap_err(), malformed);
+        assert_eq!("N0-CALL".parse::<Callsign>().unwrap_err(), malformed);
+        assert_eq!("".parse::<Callsign>().unwrap_err(), malformed);
+        assert_eq!(call("n0call-0").to_string(), "N0CALL")
+    }
+
+    #[test]
+    fn rejects_bad_addresses() {
+        let mut lower = APRS_VIA_WIDE;
+        lower[0] = b'a' << 1;
+        let mut ended = APRS_VIA_WIDE;
+        ended[6] |= 0x01;
+
+        assert_eq!(
+            Frame::parse(&lower).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0xC2 }
+        );
+        assert_eq!(
+            Frame::parse(&ended).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0xE1 }
+        );
+        assert_eq!(
+            Frame::parse(&APRS_VIA_WIDE[..21]).unwrap_err(),
+            HDLCError::MissingControl
+        );
+        assert_eq!(
+            Frame::parse(&APRS_VIA_WIDE[..22]).unwrap_err(),
+            HDLCError::MalformedMessage { protocol: "AX.25" }
+        )
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use hdlc::chdlc::{Frame, KeepaliveTracker, LineState, Protocol, Slarp, BROADCAST, UNICAST};
+    use hdlc::{decode_with_fcs, encode_with_fcs, Fcs, HDLCError, SpecialChars};
+
+    #[test]
+    fn builds_and_parses_frame() {
+        let packet = [0x45, 0x00, 0x00, 0x14];
+        let chars = SpecialChars::default();
+
+        let bytes = Frame::new(Protocol::Ipv4, &packet).to_bytes();
+        let wire = encode_with_fcs(&bytes, chars, Fcs::Fcs16);
+        let decoded = decode_with_fcs(&wire, chars, Fcs::Fcs16).unwrap();
+        let result = Frame::parse(&decoded);
+
+        assert_eq!(bytes, [0x0F, 0x00, 0x08, 0x00, 0x45, 0x00, 0x00, 0x14]);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            Frame {
+                address: UNICAST,
+                protocol: Protocol::Ipv4,
+                payload: &packet,
+            }
+        )
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        let short = Frame::parse(&[0x0F, 0x00, 0x08]);
+        let address = Frame::parse(&[0xFF, 0x03, 0x00, 0x21]);
+        let control = Frame::parse(&[0x8F, 0x03, 0x80, 0x35]);
+
+        assert_eq!(short.unwrap_err(), HDLCError::MissingProtocol);
+        assert_eq!(
+            address.unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0xFF }
+        );
+        assert_eq!(
+            control.unwrap_err(),
+            HDLCError::InvalidControl { byte: 0x03 }
+        );
+        assert_eq!(Protocol::from_u16(0x6558), Protocol::Other(0x6558))
+    }
+
+    #[test]
+    fn encodes_slarp_reply() {
+        let reply = Slarp::Reply {
+            address: Ipv4Addr::new(10, 0, 0, 1),
+            mask: Ipv4Addr::new(255, 255, 255, 252),
+        };
+
+        let result = reply.to_frame();
+
+        assert_eq!(
+            result,
+            [
+                0x8F, 0x00, 0x80, 0x35, 0x00, 0x00, 0x00, 0x01, 0x0A, 0x00, 0x00, 0x01, 0xFF, 0xFF,
+                0xFF, 0xFC, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00
+            ]
+        );
+        let frame = Frame::parse(&result).unwrap();
+        assert_eq!(frame.address, BROADCAST);
+        assert_eq!(Slarp::parse(frame.payload).unwrap(), reply)
+    }
+
+    #[test]
+    fn rejects_malformed_slarp() {
+        let short = Slarp::parse(&[0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01]);
+        let code = Slarp::parse(&[0x00, 0x00, 0x00, 0x07, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF]);
+
+        assert_eq!(
+            short.unwrap_err(),
+            HDLCError::MalformedMessage { protocol: "SLARP" }
+        );
+        assert_eq!(
+            code.unwrap_err(),
+            HDLCError::MalformedMessage { protocol: "SLARP" }
+        );
+        assert_eq!(
+            Slarp::parse(&Slarp::Request.to_bytes()).unwrap(),
+            Slarp::Request
+        )
+    }
+
+    #[test]
+    fn tracks_line_state() {
+        let mut tracker = KeepaliveTracker::new(3);
+
+        // Peer answers each keepalive
+        for n in 1..=3 {
+            assert_eq!(
+                tracker.tick(),
+                Slarp::Keepalive {
+                    sequence: n,
+                    seen: n - 1
+                }
+            );
+            tracker.receive(&Slarp::Keepalive {
+                sequence: n,
+                seen: n,
+            });
+            assert_eq!(tracker.state(), LineState::Up);
+        }
+
+        // Peer goes quiet
+        tracker.tick();
+        tracker.tick();
+        tracker.tick();
+        assert_eq!(tracker.missed(), 2);
+        assert_eq!(tracker.state(), LineState::Up);
+        tracker.tick();
+        assert_eq!(tracker.missed(), 3);
+        assert_eq!(tracker.state(), LineState::Down);
+
+        // Keepalive that crossed ours still brings the line up
+        let sent = tracker.tick();
+        tracker.receive(&Slarp::Keepalive {
+            sequence: 4,
+            seen: 6,
+        });
+        assert_eq!(
+            sent,
+            Slarp::Keepalive {
+                sequence: 8,
+                seen: 3
+            }
+        );
+        assert_eq!(tracker.state(), LineState::Down);
+        tracker.receive(&Slarp::Keepalive {
+            sequence: 5,
+            seen: 7,
+        });
+        assert_eq!(tracker.state(), LineState::Up);
+        assert_eq!(tracker.missed(), 0)
+    }
+
+    #[test]
+    fn detects_looped_line() {
+        let mut tracker = KeepaliveTracker::default();
+
+        let sent = tracker.tick();
+        tracker.receive(&sent);
+
+        assert_eq!(tracker.state(), LineState::Looped);
+        tracker.receive(&Slarp::Request);
+        assert_eq!(tracker.state(), LineState::Looped)
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "cli")]
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    fn run(args: &[&str], stdin: &[u8]) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_hdlc"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(stdin).unwrap();
+
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn encodes_hex_lines() {
+        let result = run(&["encode", "--fcs", "16"], b"FF 03\n0x01,0x7E\n");
+
+        assert!(result.status.success());
+        assert_eq!(
+            String::from_utf8(result.stdout).unwrap(),
+            "7E FF 03 1C C2 7E\n7E 01 7D 5E 66 8C 7E\n"
+        )
+    }
+
+    #[test]
+    fn decodes_binary_capture() {
+        let capture = [0x00, 0x7E, 0x01, 0x7D, 0x5E, 0x7E, 0x02, 0x7E];
+
+        let result = run(&["decode", "--binary"], &capture);
+
+        assert!(result.status.success());
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "01 7E\n02\n")
+    }
+
+    #[test]
+    fn decodes_custom_special_chars() {
+        let result = run(
+            &[
+                "decode", "--fend", "71", "--fesc", "70", "--tfend", "51", "--tfesc", "50",
+            ],
+            b"71 01 7E 70 51 71",
+        );
+
+        assert!(result.status.success());
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "01 7E 71\n")
+    }
+
+    #[test]
+    fn inspects_frames() {
+        let result = run(
+            &["inspect", "--fcs", "16"],
+            b"7E FF 03 1C C2 7E 03 10 41 42 1C C3 7E",
+        );
+
+        assert_eq!(result.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8(result.stdout).unwrap(),
+            "#0    addr 0xFF  UI                      FCS 1C C2 ok  no info\n\
+             #1    addr 0x03  I N(S)=0 N(R)=0 P/F     FCS 1C C3 bad, expected 2E 62  info 41 42\n"
+        )
+    }
+
+    #[test]
+    fn prints_fcs() {
+        let result = run(&["fcs", "--binary", "--fcs", "32"], b"123456789");
+
+        assert!(result.status.success());
+        assert_eq!(
+            String::from_utf8(result.stdout).unwrap(),
+            "0xCBF43926  wire 26 39 F4 CB\n"
+        )
+    }
+
+    #[test]
+    fn rejects_duplicate_special_chars() {
+        let result = run(&["encode", "--fesc", "7E"], b"01");
+
+        assert_eq!(result.status.code(), Some(2));
+        assert!(String::from_utf8(result.stderr)
+            .unwrap()
+            .contains("duplicate special character"))
+    }
+
+    #[test]
+    fn rejects_bare_hex_prefix() {
+        let result = run(&["encode"], b"01 0x 02");
+
+        assert_eq!(result.status.code(), Some(2));
+        assert!(String::from_utf8(result.stderr)
+            .unwrap()
+            .contains("odd number of hex digits in `0x`"))
+    }
+
+    #[test]
+    fn monitors_stream() {
+        let capture = [
+            0x7E, 0xFF, 0x03, 0x1C, 0xC2, 0x7E, 0x01, 0x7D, 0x7E, 0x03, 0x10, 0x41, 0x42, 0x1C,
+            0xC3, 0x7E,
+        ];
+
+        let result = run(
+            &["monitor", "-", "--fcs", "16", "--timestamps", "none"],
+            &capture,
+        );
+
+        assert_eq!(result.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8(result.stdout).unwrap(),
+            "#0    addr 0xFF  UI                      FCS 1C C2 ok  no info\n\
+             #1    aborted by the sender\n\
+             #2    addr 0x03  I N(S)=0 N(R)=0 P/F     FCS 1C C3 bad, expected 2E 62  info 41 42\n"
+        )
+    }
+
+    #[test]
+    fn monitor_reports_missing_device() {
+        let result = run(&["monitor", "/nonexistent/ttyUSB9"], b"");
+
+        assert_eq!(result.status.code(), Some(2));
+        assert!(String::from_utf8(result.stderr)
+            .unwrap()
+            .starts_with("hdlc: /nonexistent/ttyUSB9"))
+    }
+}
@@ -0,0 +1,570 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::decoder::{
+        decode_frames, ClosingFend, CorruptFrame, DecodeOptions, DecoderState, ErrorPolicy,
+        FrameDecoder, FrameEvent, OpeningFend, RuntPolicy,
+    };
+    use hdlc::{HDLCError, SpecialChars};
+
+    fn feed_all<const N: usize>(decoder: &mut FrameDecoder<N>, bytes: &[u8]) -> Vec<String> {
+        bytes
+            .iter()
+            .filter_map(|&b| decoder.feed(b).map(|event| format!("{:?}", event)))
+            .collect()
+    }
+
+    #[test]
+    fn decodes_bytewise() {
+        let chars = SpecialChars::default();
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars);
+        let msg = [
+            0x00,
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.tfesc(),
+            0x00,
+            chars.fesc(),
+            chars.tfend(),
+            0x09,
+            chars.fend(),
+        ];
+        let mut frames = Vec::new();
+
+        for byte in msg {
+            if let Some(FrameEvent::Frame(frame)) = decoder.feed(byte) {
+                frames.push(frame.to_vec());
+            }
+        }
+
+        assert_eq!(frames, vec![vec![1, 125, 0, 126, 9]]);
+        assert_eq!(decoder.state(), DecoderState::InFrame)
+    }
+
+    #[test]
+    fn decodes_shared_flags_and_fill() {
+        let chars = SpecialChars::default();
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars);
+        let msg = [
+            chars.fend(),
+            chars.fend(),
+            0x01,
+            chars.fend(),
+            0x02,
+            chars.fend(),
+            chars.fend(),
+            0x03,
+            chars.fend(),
+        ];
+
+        let events = feed_all(&mut decoder, &msg);
+
+        assert_eq!(events, ["Frame([1])", "Frame([2])", "Frame([3])"])
+    }
+
+    #[test]
+    fn reports_abort() {
+        let chars = SpecialChars::default();
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars);
+        let msg = [
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.fend(),
+            0x02,
+            chars.fend(),
+        ];
+
+        let events = feed_all(&mut decoder, &msg);
+
+        assert_eq!(events, ["Abort", "Frame([2])"])
+    }
+
+    #[test]
+    fn reports_overflow_and_resyncs() {
+        let chars = SpecialChars::default();
+        let mut decoder: FrameDecoder<2> = FrameDecoder::new(chars);
+        let msg = [
+            chars.fend(),
+            0x01,
+            0x02,
+            0x03,
+            0x04,
+            chars.fend(),
+            0x05,
+            chars.fend(),
+        ];
+
+        let events = feed_all(&mut decoder, &msg);
+
+        assert_eq!(events, ["Overflow", "Frame([5])"])
+    }
+
+    #[test]
+    fn reports_stray_fesc() {
+        let chars = SpecialChars::default();
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars);
+
+        decoder.feed(chars.fend());
+        decoder.feed(0x01);
+        decoder.feed(chars.fesc());
+
+        assert_eq!(decoder.state(), DecoderState::Escape);
+        assert_eq!(
+            decoder.feed(0x00),
+            Some(FrameEvent::Error(HDLCError::MissingTradeChar {
+                offset: 3,
+                byte: Some(0x00),
+                frame: 0
+            }))
+        );
+        assert_eq!(decoder.state(), DecoderState::Hunting)
+    }
+
+    #[test]
+    fn discards_bad_frames_bytewise() {
+        let chars = SpecialChars::default();
+        let mut decoder: FrameDecoder<2> =
+            FrameDecoder::new(chars).with_policy(ErrorPolicy::Discard);
+        let msg = [
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            0x00,
+            chars.fend(),
+            0x01,
+            0x02,
+            0x03,
+            chars.fend(),
+            0x04,
+            chars.fend(),
+        ];
+
+        let events = feed_all(&mut decoder, &msg);
+
+        assert_eq!(events, ["Frame([4])"])
+    }
+
+    #[test]
+    fn decodes_frames_in_buffer() {
+        let chars = SpecialChars::default();
+        let msg = [
+            0x55,
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.tfend(),
+            chars.fend(),
+            chars.fend(),
+            0x02,
+            chars.fend(),
+        ];
+
+        let frames: Vec<_> = decode_frames(&msg, chars).collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                Err(CorruptFrame {
+                    error: HDLCError::MissingFirstFend { byte: Some(0x55) },
+                    raw: &[0x55],
+                }),
+                Ok(vec![0x01, 0x7E]),
+                Ok(vec![0x02])
+            ]
+        )
+    }
+
+    #[test]
+    fn reports_bad_frame_and_resyncs() {
+        let chars = SpecialChars::default();
+        let msg = [
+            chars.fend(),
+            0x01,
+            chars.fend(),
+            0x02,
+            chars.fesc(),
+            0x00,
+            chars.fend(),
+            0x03,
+            chars.fend(),
+        ];
+
+        let frames: Vec<_> = decode_frames(&msg, chars).collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                Ok(vec![0x01]),
+                Err(CorruptFrame {
+                    error: HDLCError::MissingTradeChar {
+                        offset: 5,
+                        byte: Some(0x00),
+                        frame: 1
+                    },
+                    raw: &[0x02, chars.fesc(), 0x00],
+                }),
+                Ok(vec![0x03]),
+            ]
+        )
+    }
+
+    #[test]
+    fn discards_bad_frames_in_buffer() {
+        let chars = SpecialChars::default();
+        let msg = [
+            chars.fend(),
+            chars.fesc(),
+            chars.fend(),
+            0x02,
+            chars.fesc(),
+            0x00,
+            chars.fend(),
+            0x03,
+            chars.fend(),
+        ];
+
+        let frames: Vec<_> = decode_frames(&msg, chars)
+            .with_policy(ErrorPolicy::Discard)
+            .collect();
+
+        assert_eq!(frames, vec![Ok(vec![0x03])])
+    }
+
+    #[test]
+    fn keeps_partial_frame_as_remainder() {
+        let chars = SpecialChars::default();
+        let first = [chars.fend(), 0x01, chars.fend(), 0x02];
+        let second = [0x03, chars.fend()];
+
+        let mut frames = decode_frames(&first, chars);
+        assert_eq!(frames.next(), Some(Ok(vec![0x01])));
+        assert_eq!(frames.next(), None);
+
+        let mut next = frames.remainder().to_vec();
+        next.extend_from_slice(&second);
+        let frames: Vec<_> = decode_frames(&next, chars).collect();
+
+        assert_eq!(frames, vec![Ok(vec![0x02, 0x03])])
+    }
+
+    #[test]
+    fn reports_stream_position() {
+        let chars = SpecialChars::default();
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars);
+        let msg = [
+            0x00,
+            chars.fend(),
+            0x01,
+            chars.fend(),
+            chars.fend(),
+            0x02,
+            chars.fesc(),
+            0x03,
+        ];
+
+        let events = feed_all(&mut decoder, &msg);
+
+        assert_eq!(
+            events,
+            [
+                "Error(MissingFirstFend { byte: Some(0) })",
+                "Frame([1])",
+                "Error(MissingTradeChar { offset: 7, byte: Some(3), frame: 1 })"
+            ]
+        )
+    }
+
+    #[test]
+    fn reports_aborted_frame_in_buffer() {
+        let chars = SpecialChars::default();
+        let msg = [
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.fend(),
+            0x02,
+            chars.fend(),
+        ];
+
+        let frames: Vec<_> = decode_frames(&msg, chars).collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                Err(CorruptFrame {
+                    error: HDLCError::Aborted {
+                        offset: 2,
+                        frame: 0
+                    },
+                    raw: &[0x01, chars.fesc()],
+                }),
+                Ok(vec![0x02]),
+            ]
+        )
+    }
+
+    #[test]
+    fn reports_frame_too_long_and_resyncs() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            max_frame_len: Some(2),
+            ..DecodeOptions::default()
+        };
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars).with_options(options);
+        let msg = [
+            chars.fend(),
+            0x01,
+            0x02,
+            0x03,
+            0x04,
+            chars.fend(),
+            0x05,
+            0x06,
+            chars.fend(),
+        ];
+
+        let events = feed_all(&mut decoder, &msg);
+
+        assert_eq!(
+            events,
+            [
+                "Error(FrameTooLong { offset: 3, max: 2, frame: 0 })",
+                "Frame([5, 6])"
+            ]
+        )
+    }
+
+    #[test]
+    fn rejects_long_frame_in_buffer() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            max_frame_len: Some(2),
+            ..DecodeOptions::default()
+        };
+        let msg = [
+            chars.fend(),
+            0x01,
+            chars.fend(),
+            0x02,
+            0x03,
+            0x04,
+            chars.fend(),
+            0x05,
+            chars.fend(),
+        ];
+
+        let frames: Vec<_> = decode_frames(&msg, chars).with_options(options).collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                Ok(vec![0x01]),
+                Err(CorruptFrame {
+                    error: HDLCError::FrameTooLong {
+                        offset: 5,
+                        max: 2,
+                        frame: 1
+                    },
+                    raw: &[0x02, 0x03, 0x04],
+                }),
+                Ok(vec![0x05]),
+            ]
+        )
+    }
+
+    #[test]
+    fn fails_unclosed_frame_across_chunks() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            max_frame_len: Some(8),
+            opening_fend: OpeningFend::Hunt,
+            ..DecodeOptions::default()
+        };
+        let chunks: [&[u8]; 4] = [
+            &[chars.fend(), 0x01, 0x02, 0x03],
+            &[0x04, 0x05, chars.fesc(), chars.tfend(), 0x06],
+            &[0x07, 0x08, 0x09, 0x0A, 0x0B],
+            &[0x0C, chars.fend(), 0x0D, chars.fend()],
+        ];
+        let mut pending = Vec::new();
+        let mut results = Vec::new();
+
+        for chunk in chunks {
+            pending.extend_from_slice(chunk);
+            let mut frames = decode_frames(&pending, chars).with_options(options);
+            results.extend(
+                frames
+                    .by_ref()
+                    .map(|result| result.map_err(|bad| bad.error)),
+            );
+            pending = frames.remainder().to_vec();
+            assert!(pending.len() <= 1 + 2 * 8);
+        }
+
+        assert_eq!(
+            results,
+            vec![
+                Err(HDLCError::FrameTooLong {
+                    offset: 10,
+                    max: 8,
+                    frame: 0
+                }),
+                Ok(vec![0x0D]),
+            ]
+        );
+        assert_eq!(pending, [chars.fend()])
+    }
+
+    #[test]
+    fn reports_and_skips_runts() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            min_frame_len: 2,
+            ..DecodeOptions::default()
+        };
+        let msg = [chars.fend(), 0x01, chars.fend(), 0x02, 0x03, chars.fend()];
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars).with_options(options);
+
+        let events = feed_all(&mut decoder, &msg);
+        let frames: Vec<_> = decode_frames(&msg, chars)
+            .with_options(DecodeOptions {
+                runts: RuntPolicy::Skip,
+                ..options
+            })
+            .collect();
+
+        assert_eq!(
+            events,
+            [
+                "Error(RuntFrame { offset: 2, len: 1, min: 2, frame: 0 })",
+                "Frame([2, 3])"
+            ]
+        );
+        assert_eq!(frames, vec![Ok(vec![0x02, 0x03])])
+    }
+
+    #[test]
+    fn decodes_slip_without_opening_end() {
+        let chars = SpecialChars::SLIP;
+        let options = DecodeOptions {
+            opening_fend: OpeningFend::Optional,
+            ..DecodeOptions::default()
+        };
+        let msg = [0x01, 0x02, 0xC0, 0x03, 0xDB, 0x00, 0xC0, 0x04, 0xC0];
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars).with_options(options);
+
+        let events = feed_all(&mut decoder, &msg);
+        let frames: Vec<_> = decode_frames(&msg, chars).with_options(options).collect();
+        let strict: Vec<_> = decode_frames(&msg, chars).collect();
+
+        assert_eq!(
+            events,
+            [
+                "Frame([1, 2])",
+                "Error(MissingTradeChar { offset: 5, byte: Some(0), frame: 1 })",
+                "Frame([4])"
+            ]
+        );
+        assert_eq!(
+            frames,
+            vec![
+                Ok(vec![0x01, 0x02]),
+                Err(CorruptFrame {
+                    error: HDLCError::MissingTradeChar {
+                        offset: 5,
+                        byte: Some(0x00),
+                        frame: 1
+                    },
+                    raw: &[0x03, 0xDB, 0x00],
+                }),
+                Ok(vec![0x04]),
+            ]
+        );
+        assert_eq!(
+            strict[0].as_ref().unwrap_err().error,
+            HDLCError::MissingFirstFend { byte: Some(0x01) }
+        );
+        assert_eq!(strict.len(), 3)
+    }
+
+    #[test]
+    fn yields_trailing_frame_without_closing_fend() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            opening_fend: OpeningFend::Hunt,
+            closing_fend: ClosingFend::Optional,
+            ..DecodeOptions::default()
+        };
+        let msg = [0x00, chars.fend(), 0x01, chars.fend(), 0x02, chars.fesc()];
+
+        let mut frames = decode_frames(&msg, chars).with_options(options);
+
+        assert_eq!(frames.next().unwrap(), Ok(vec![0x01]));
+        assert_eq!(
+            frames.next().unwrap(),
+            Err(CorruptFrame {
+                error: HDLCError::MissingTradeChar {
+                    offset: 6,
+                    byte: None,
+                    frame: 1
+                },
+                raw: &[0x02, chars.fesc()],
+            })
+        );
+        assert_eq!(frames.next(), None);
+        assert_eq!(frames.remainder(), []);
+        assert_eq!(
+            decode_frames(&msg[..5], chars)
+                .with_options(options)
+                .collect::<Vec<_>>(),
+            vec![Ok(vec![0x01]), Ok(vec![0x02])]
+        )
+    }
+
+    #[test]
+    fn reports_leading_garbage() {
+        let chars = SpecialChars::default();
+        let msg = [0x01, 0x02, chars.fend(), 0x05, chars.fend()];
+        let mut decoder: FrameDecoder<16> = FrameDecoder::new(chars);
+
+        let events = feed_all(&mut decoder, &msg);
+        let frames: Vec<_> = decode_frames(&msg, chars).collect();
+        let single = hdlc::decode(&msg, chars);
+
+        assert_eq!(
+            events,
+            ["Error(MissingFirstFend { byte: Some(1) })", "Frame([5])"]
+        );
+        assert_eq!(
+            frames,
+            vec![
+                Err(CorruptFrame {
+                    error: HDLCError::MissingFirstFend { byte: Some(0x01) },
+                    raw: &[0x01, 0x02],
+                }),
+                Ok(vec![0x05])
+            ]
+        );
+        assert_eq!(
+            single,
+            Err(HDLCError::MissingFirstFend { byte: Some(0x01) })
+        )
+    }
+
+    #[test]
+    fn hunts_past_leading_garbage() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            opening_fend: OpeningFend::Hunt,
+            ..DecodeOptions::default()
+        };
+        let msg = [0x33, 0x44, chars.fend(), 0x01, chars.fend()];
+
+        let frames: Vec<_> = decode_frames(&msg, chars).with_options(options).collect();
+        let single = hdlc::decode_with_options(&msg, chars, options);
+
+        assert_eq!(frames, vec![Ok(vec![0x01])]);
+        assert_eq!(single.unwrap(), [0x01])
+    }
+}
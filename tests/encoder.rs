@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::encoder::{EncodeOptions, Encoder, IdleFill};
+    use hdlc::{decode_frames, HDLCError, SpecialChars};
+
+    #[test]
+    fn encodes_like_encode() {
+        let msg = [0x01, 0x7E, 0x00, 0x7D, 0x00, 0x05, 0x80, 0x09];
+        let chars = SpecialChars::default();
+        let mut encoder = Encoder::new(chars);
+
+        let first = encoder.encode(&msg).unwrap();
+        let second = encoder.encode(&msg).unwrap();
+
+        assert_eq!(first, hdlc::encode(&msg, chars).unwrap());
+        assert_eq!(second, first)
+    }
+
+    #[test]
+    fn doubles_opening_flag() {
+        let chars = SpecialChars::default();
+        let options = EncodeOptions {
+            opening_flags: 2,
+            ..EncodeOptions::default()
+        };
+        let mut encoder = Encoder::new(chars).with_options(options);
+
+        let result = encoder.encode(&[0x01]).unwrap();
+
+        assert_eq!(result, [chars.fend(), chars.fend(), 0x01, chars.fend()])
+    }
+
+    #[test]
+    fn shares_flags_between_frames() {
+        let chars = SpecialChars::default();
+        let options = EncodeOptions {
+            opening_flags: 2,
+            shared_flags: true,
+            ..EncodeOptions::default()
+        };
+        let mut encoder = Encoder::new(chars).with_options(options);
+        let mut wire = Vec::new();
+
+        encoder.encode_to_vec(&[0x01], &mut wire).unwrap();
+        encoder.encode_to_vec(&[0x02], &mut wire).unwrap();
+        encoder.reset();
+        encoder.encode_to_vec(&[0x03], &mut wire).unwrap();
+
+        assert_eq!(
+            wire,
+            [
+                chars.fend(),
+                chars.fend(),
+                0x01,
+                chars.fend(),
+                0x02,
+                chars.fend(),
+                chars.fend(),
+                chars.fend(),
+                0x03,
+                chars.fend(),
+            ]
+        )
+    }
+
+    #[test]
+    fn flag_idle_keeps_frames_decodable() {
+        let chars = SpecialChars::default();
+        let options = EncodeOptions {
+            shared_flags: true,
+            ..EncodeOptions::default()
+        };
+        let mut encoder = Encoder::new(chars).with_options(options);
+        let mut wire = Vec::new();
+
+        encoder.idle(IdleFill::Flags, 3, &mut wire);
+        encoder.encode_to_vec(&[0x01], &mut wire).unwrap();
+        encoder.idle(IdleFill::Flags, 3, &mut wire);
+        encoder.encode_to_vec(&[0x02], &mut wire).unwrap();
+        encoder.encode_to_vec(&[0x03], &mut wire).unwrap();
+
+        let frames: Vec<_> = decode_frames(&wire, chars).collect();
+
+        assert_eq!(wire.len(), 12);
+        assert_eq!(frames, vec![Ok(vec![0x01]), Ok(vec![0x02]), Ok(vec![0x03])])
+    }
+
+    #[test]
+    fn mark_idle_reopens_frame() {
+        let chars = SpecialChars::default();
+        let options = EncodeOptions {
+            shared_flags: true,
+            ..EncodeOptions::default()
+        };
+        let mut encoder = Encoder::new(chars).with_options(options);
+        let mut wire = Vec::new();
+
+        encoder.encode_to_vec(&[0x01], &mut wire).unwrap();
+        encoder.idle(IdleFill::Mark, 2, &mut wire);
+        encoder.encode_to_vec(&[0x02], &mut wire).unwrap();
+
+        assert_eq!(
+            wire,
+            [
+                chars.fend(),
+                0x01,
+                chars.fend(),
+                0xFF,
+                0xFF,
+                chars.fend(),
+                0x02,
+                chars.fend()
+            ]
+        )
+    }
+
+    #[test]
+    fn mark_idle_round_trips() {
+        let chars = SpecialChars::default();
+        let mut encoder = Encoder::new(chars);
+        let mut wire = Vec::new();
+
+        encoder.encode_to_vec(&[0x01], &mut wire).unwrap();
+        encoder.idle(IdleFill::Mark, 4, &mut wire);
+        encoder.encode_to_vec(&[0xFF, 0x03], &mut wire).unwrap();
+
+        let decoded: Vec<_> = decode_frames(&wire, chars).map(Result::unwrap).collect();
+        let frames: Vec<_> = decoded
+            .iter()
+            .filter(|frame| !IdleFill::Mark.is_idle(frame))
+            .collect();
+
+        assert_eq!(decoded[1], [0xFF; 4]);
+        assert_eq!(frames, [&vec![0x01], &vec![0xFF, 0x03]]);
+        assert!(!IdleFill::Mark.is_idle(&[]))
+    }
+
+    #[test]
+    fn rejects_duplicate_special_chars() {
+        let result = SpecialChars::new(0x7E, 0x7E, 0x5E, 0x5D);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), HDLCError::DuplicateSpecialChar)
+    }
+
+    #[test]
+    fn refuses_payload_over_max_frame_len() {
+        let chars = SpecialChars::default();
+        let options = EncodeOptions {
+            max_frame_len: Some(2),
+            ..EncodeOptions::default()
+        };
+        let mut encoder = Encoder::new(chars).with_options(options);
+        let mut wire = Vec::new();
+
+        let result = encoder.encode_to_vec(&[0x01, 0x02, 0x03], &mut wire);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::PayloadTooLong { len: 3, max: 2 }
+        );
+        assert!(wire.is_empty());
+        assert_eq!(
+            encoder.encode(&[0x01, 0x7E]).unwrap(),
+            [0x7E, 0x01, 0x7D, 0x5E, 0x7E]
+        )
+    }
+}
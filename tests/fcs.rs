@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::fcs::{Crc, CrcParams, Fcs16, Fcs32};
+    use hdlc::{
+        decode, decode_slice_with_fcs, decode_with_fcs, encode_parts_with_fcs, encode_with_fcs,
+        Fcs, HDLCError, SpecialChars,
+    };
+
+    // Bit at a time reference, independent of the engine picked by the cargo features
+    fn reference(data: &[u8], poly: u32, init: u32) -> u32 {
+        let mut crc = init;
+        for &byte in data {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ poly
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc & init
+    }
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 + 7) as u8).collect()
+    }
+
+    #[test]
+    fn fcs16_check_value() {
+        assert_eq!(Fcs16::checksum(b"123456789"), 0x906E);
+        assert_eq!(Fcs16::checksum(&[]), 0x0000)
+    }
+
+    #[test]
+    fn fcs32_check_value() {
+        assert_eq!(Fcs32::checksum(b"123456789"), 0xCBF4_3926);
+        assert_eq!(Fcs32::checksum(&[]), 0x0000_0000)
+    }
+
+    #[test]
+    fn fcs_streams_across_splits() {
+        let data = sample(1031);
+
+        for split in [0, 1, 7, 8, 9, 500, 1030, 1031] {
+            let mut fcs16 = Fcs16::new();
+            let mut fcs32 = Fcs32::new();
+            fcs16.update(&data[..split]);
+            fcs16.update(&data[split..]);
+            fcs32.update(&data[..split]);
+            fcs32.update(&data[split..]);
+
+            assert_eq!(u32::from(fcs16.finish()), reference(&data, 0x8408, 0xFFFF));
+            assert_eq!(fcs32.finish(), reference(&data, 0xEDB8_8320, 0xFFFF_FFFF));
+        }
+    }
+
+    #[test]
+    fn pack_with_fcs16() {
+        let msg = [0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09];
+        let chars = SpecialChars::default();
+        let fcs = Fcs16::checksum(&msg).to_le_bytes();
+
+        let result = encode_with_fcs(&msg, chars, Fcs::Fcs16);
+
+        let mut cmp = msg.to_vec();
+        cmp.extend_from_slice(&fcs);
+        assert_eq!(decode(&result, chars).unwrap(), cmp)
+    }
+
+    #[test]
+    fn pack_parts_with_fcs32() {
+        let chars = SpecialChars::default();
+        let msg = sample(300);
+
+        let joined = encode_with_fcs(&msg, chars, Fcs::Fcs32);
+        let parts = encode_parts_with_fcs(&[&msg[..10], &msg[10..]], chars, Fcs::Fcs32);
+
+        assert_eq!(parts, joined)
+    }
+
+    #[test]
+    fn pack_stuffs_fcs_bytes() {
+        // Pick a payload whose FCS-16 low byte needs escaping
+        let chars = SpecialChars::default();
+        let msg = (0..=255u8)
+            .find(|&b| Fcs16::checksum(&[b]) & 0xFF == 0x7E)
+            .unwrap();
+
+        let result = encode_with_fcs(&[msg], chars, Fcs::Fcs16);
+
+        assert_eq!(result[2..4], [chars.fesc(), chars.tfend()]);
+        assert_eq!(decode_with_fcs(&result, chars, Fcs::Fcs16).unwrap(), [msg])
+    }
+
+    #[test]
+    fn depack_with_fcs() {
+        let chars = SpecialChars::default();
+        let msg = sample(64);
+
+        for fcs in [Fcs::Fcs16, Fcs::Fcs32] {
+            let mut encoded = encode_with_fcs(&msg, chars, fcs);
+
+            assert_eq!(decode_with_fcs(&encoded, chars, fcs).unwrap(), msg);
+            assert_eq!(
+                decode_slice_with_fcs(&mut encoded, chars, fcs).unwrap(),
+                msg
+            );
+        }
+    }
+
+    #[test]
+    fn depack_rejects_bad_fcs() {
+        let chars = SpecialChars::default();
+        let mut encoded = encode_with_fcs(&[0x01, 0x02, 0x03], chars, Fcs::Fcs16);
+        encoded[2] ^= 0x10;
+
+        let result = decode_with_fcs(&encoded, chars, Fcs::Fcs16);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), HDLCError::FcsMismatch)
+    }
+
+    #[test]
+    fn depack_rejects_missing_fcs() {
+        let chars = SpecialChars::default();
+        let msg = [chars.fend(), 0x01, 0x02, 0x03, chars.fend()];
+
+        let result = decode_with_fcs(&msg, chars, Fcs::Fcs32);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), HDLCError::MissingFcs)
+    }
+
+    fn crc_check(params: CrcParams) -> u32 {
+        let mut crc = Crc::new(params).unwrap();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        crc.finish()
+    }
+
+    #[test]
+    fn custom_crc_check_values() {
+        let crc8 = CrcParams {
+            width: 8,
+            poly: 0x07,
+            init: 0x00,
+            refin: false,
+            refout: false,
+            xorout: 0x00,
+        };
+        let crc24 = CrcParams {
+            width: 24,
+            poly: 0x86_4CFB,
+            init: 0xB7_04CE,
+            refin: false,
+            refout: false,
+            xorout: 0x00_0000,
+        };
+
+        assert_eq!(crc_check(CrcParams::CRC16_X25), 0x906E);
+        assert_eq!(crc_check(CrcParams::CRC32_ISO_HDLC), 0xCBF4_3926);
+        assert_eq!(crc_check(CrcParams::CRC16_MODBUS), 0x4B37);
+        assert_eq!(crc_check(CrcParams::CRC16_KERMIT), 0x2189);
+        assert_eq!(crc_check(CrcParams::CRC16_IBM_3740), 0x29B1);
+        assert_eq!(crc_check(CrcParams::CRC32_BZIP2), 0xFC89_1918);
+        assert_eq!(crc_check(crc8), 0xF4);
+        assert_eq!(crc_check(crc24), 0x21_CF02)
+    }
+
+    #[test]
+    fn custom_crc_rejects_odd_width() {
+        let params = CrcParams {
+            width: 12,
+            ..CrcParams::CRC16_MODBUS
+        };
+
+        let result = Crc::new(params);
+
+        assert_eq!(result.unwrap_err(), HDLCError::InvalidCrcParams)
+    }
+
+    #[test]
+    fn custom_fcs_matches_preset() {
+        let chars = SpecialChars::default();
+        let msg = sample(100);
+
+        let crc = Crc::new(CrcParams::CRC16_X25).unwrap();
+
+        let preset = encode_with_fcs(&msg, chars, Fcs::Fcs16);
+        let custom = encode_with_fcs(&msg, chars, Fcs::Custom(&crc));
+
+        assert_eq!(preset, custom);
+        assert_eq!(Fcs::Custom(&crc).size(), 2)
+    }
+
+    #[test]
+    fn custom_fcs_byte_order() {
+        let chars = SpecialChars::default();
+        let msg = b"123456789";
+
+        let modbus = Crc::new(CrcParams::CRC16_MODBUS).unwrap();
+        let ibm = Crc::new(CrcParams::CRC16_IBM_3740).unwrap();
+
+        let modbus_frame = encode_with_fcs(msg, chars, Fcs::Custom(&modbus));
+        let ibm_frame = encode_with_fcs(msg, chars, Fcs::Custom(&ibm));
+        let again = encode_with_fcs(msg, chars, Fcs::Custom(&ibm));
+
+        // Reflected CRCs go least significant byte first, the others most significant first
+        assert_eq!(modbus_frame[10..12], [0x37, 0x4B]);
+        assert_eq!(ibm_frame[10..12], [0x29, 0xB1]);
+        assert_eq!(again, ibm_frame);
+        assert_eq!(
+            decode_with_fcs(&ibm_frame, chars, Fcs::Custom(&ibm)).unwrap(),
+            msg
+        )
+    }
+
+    #[test]
+    fn computes_and_verifies_decoded_frame() {
+        let msg = b"123456789";
+
+        let fcs = Fcs::Fcs32.compute(msg);
+        let mut frame = msg.to_vec();
+        frame.extend_from_slice(&fcs);
+
+        assert_eq!(fcs, [0x26, 0x39, 0xF4, 0xCB]);
+        assert_eq!(Fcs::Fcs32.verify(&frame).unwrap(), msg);
+        frame[0] ^= 0x01;
+        assert_eq!(Fcs::Fcs32.verify(&frame), Err(HDLCError::FcsMismatch));
+        assert_eq!(Fcs::Fcs32.verify(&frame[..3]), Err(HDLCError::MissingFcs))
+    }
+}
@@ -0,0 +1,223 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::frame::{Control, ExtendedControl, Frame, Supervisory, Unnumbered};
+    use hdlc::{Fcs, HDLCError, SpecialChars};
+
+    #[test]
+    fn parses_i_frame() {
+        let msg = [0x03, 0x5A, 0x01, 0x02];
+
+        let result = Frame::parse(&msg);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            Frame {
+                address: 0x03,
+                control: Control::Information {
+                    ns: 5,
+                    nr: 2,
+                    pf: true
+                },
+                info: &[0x01, 0x02],
+            }
+        )
+    }
+
+    #[test]
+    fn parses_s_and_u_frames() {
+        assert_eq!(
+            Control::from_byte(0x89),
+            Control::Supervisory {
+                kind: Supervisory::Rej,
+                nr: 4,
+                pf: false
+            }
+        );
+        assert_eq!(
+            Control::from_byte(0x3F),
+            Control::Unnumbered {
+                kind: Unnumbered::Sabm,
+                pf: true
+            }
+        );
+        assert_eq!(
+            Control::from_byte(0x13),
+            Control::Unnumbered {
+                kind: Unnumbered::Ui,
+                pf: true
+            }
+        )
+    }
+
+    #[test]
+    fn control_round_trips() {
+        for byte in 0..=255u8 {
+            assert_eq!(Control::from_byte(byte).to_byte(), byte);
+        }
+    }
+
+    #[test]
+    fn displays_control() {
+        assert_eq!(Control::from_byte(0x5A).to_string(), "I N(S)=5 N(R)=2 P/F");
+        assert_eq!(Control::from_byte(0x63).to_string(), "UA");
+        assert_eq!(Control::from_byte(0x1B).to_string(), "U 0x0B P/F")
+    }
+
+    #[test]
+    fn rejects_short_frame() {
+        let result = Frame::parse(&[0x03]);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), HDLCError::MissingControl)
+    }
+
+    #[test]
+    fn frame_to_bytes() {
+        let frame = Frame {
+            address: 0x01,
+            control: Control::Unnumbered {
+                kind: Unnumbered::Test,
+                pf: false,
+            },
+            info: b"ok",
+        };
+
+        assert_eq!(frame.to_bytes(), [0x01, 0xE3, b'o', b'k'])
+    }
+
+    #[test]
+    fn parses_extended_control() {
+        let i_frame = ExtendedControl::parse(&[0xC8, 0xF5, 0xAA]);
+        let s_frame = ExtendedControl::parse(&[0x0D, 0x02]);
+        let u_frame = ExtendedControl::parse(&[0x7F, 0xAA]);
+
+        assert_eq!(
+            i_frame.unwrap(),
+            (
+                ExtendedControl::Information {
+                    ns: 100,
+                    nr: 122,
+                    pf: true
+                },
+                &[0xAA][..]
+            )
+        );
+        assert_eq!(
+            s_frame.unwrap(),
+            (
+                ExtendedControl::Supervisory {
+                    kind: Supervisory::Srej,
+                    nr: 1,
+                    pf: false
+                },
+                &[][..]
+            )
+        );
+        assert_eq!(
+            u_frame.unwrap(),
+            (
+                ExtendedControl::Unnumbered {
+                    kind: Unnumbered::Sabme,
+                    pf: true
+                },
+                &[0xAA][..]
+            )
+        );
+        assert_eq!(
+            ExtendedControl::parse(&[0xC8, 0xF5]).unwrap().0.to_string(),
+            "I N(S)=100 N(R)=122 P/F"
+        )
+    }
+
+    #[test]
+    fn extended_control_round_trips() {
+        for first in 0..=255u8 {
+            for second in [0x00, 0x01, 0xFE, 0xFF] {
+                let field = [first, second];
+                match ExtendedControl::parse(&field) {
+                    Ok((control, rest)) => {
+                        assert_eq!(control.to_bytes(), field[..field.len() - rest.len()])
+                    }
+                    Err(e) => assert_eq!(e, HDLCError::InvalidControl { byte: first }),
+                }
+            }
+        }
+        assert_eq!(
+            ExtendedControl::parse(&[0x01]).unwrap_err(),
+            HDLCError::MissingControl
+        );
+        assert_eq!(
+            ExtendedControl::parse(&[]).unwrap_err(),
+            HDLCError::MissingControl
+        )
+    }
+
+    #[test]
+    fn builds_encoded_frames() {
+        let chars = SpecialChars::default();
+
+        let sabm = Frame::command(0x01)
+            .u_frame(Unnumbered::Sabm, true)
+            .encode(chars, Fcs::Fcs16);
+        let rr = Frame::response(0x03)
+            .s_frame(Supervisory::Rr, 5, false)
+            .encode(chars, Fcs::Fcs32);
+        let ui = Frame::command(0xFF).info(b"hi").build();
+
+        assert!(sabm.is_ok());
+        assert_eq!(sabm.unwrap(), [0x7E, 0x01, 0x3F, 0xEB, 0xDF, 0x7E]);
+        assert_eq!(
+            rr.unwrap(),
+            [0x7E, 0x03, 0xA1, 0x42, 0xD2, 0x25, 0xCB, 0x7E]
+        );
+        assert_eq!(
+            ui.unwrap(),
+            Frame::parse(&[0xFF, 0x03, b'h', b'i']).unwrap()
+        )
+    }
+
+    #[test]
+    fn builder_rejects_bad_fields() {
+        let sequence = Frame::command(0x01).i_frame(8, 0, false).build();
+        let ua_command = Frame::command(0x01).u_frame(Unnumbered::Ua, false).build();
+        let disc_response = Frame::response(0x01)
+            .u_frame(Unnumbered::Disc, true)
+            .build();
+        let rr_info = Frame::command(0x01)
+            .s_frame(Supervisory::Rr, 0, false)
+            .info(&[0x00])
+            .build();
+
+        assert_eq!(
+            sequence.unwrap_err(),
+            HDLCError::SequenceOutOfRange {
+                value: 8,
+                modulus: 8
+            }
+        );
+        assert_eq!(
+            ua_command.unwrap_err(),
+            HDLCError::InvalidControl { byte: 0x63 }
+        );
+        assert_eq!(
+            disc_response.unwrap_err(),
+            HDLCError::InvalidControl { byte: 0x53 }
+        );
+        assert_eq!(
+            rr_info.unwrap_err(),
+            HDLCError::InvalidControl { byte: 0x01 }
+        );
+        for byte in [0x00, 0x01, 0x02] {
+            let other = Frame::command(0x01)
+                .u_frame(Unnumbered::Other(byte), false)
+                .build();
+
+            assert_eq!(other.unwrap_err(), HDLCError::InvalidControl { byte });
+        }
+        assert!(Frame::command(0x01)
+            .u_frame(Unnumbered::Other(0x07), false)
+            .build()
+            .is_ok())
+    }
+}
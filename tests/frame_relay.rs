@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::frame_relay::{
+        Address, AddressLen, Frame, LmiMessage, LmiMessageKind, LmiPoller, LmiVariant, PvcStatus,
+        ReportType,
+    };
+    use hdlc::HDLCError;
+
+    fn pvc(dlci: u16, new: bool, active: bool) -> PvcStatus {
+        PvcStatus {
+            dlci,
+            new,
+            deleted: false,
+            active,
+        }
+    }
+
+    #[test]
+    fn parses_two_byte_address() {
+        let msg = [0xFA, 0x0B, 0x03, 0xCC];
+
+        let result = Frame::parse(&msg);
+
+        assert!(result.is_ok());
+        let frame = result.unwrap();
+        assert_eq!(
+            frame.address,
+            Address {
+                dlci: 992,
+                cr: true,
+                fecn: true,
+                becn: false,
+                de: true,
+                len: AddressLen::Two,
+            }
+        );
+        assert_eq!(frame.payload, [0x03, 0xCC]);
+        assert_eq!(frame.to_bytes(), msg)
+    }
+
+    #[test]
+    fn address_round_trips() {
+        for dlci in [0, 16, 1007, 1023, 1024, 0xABCD, 0xFFFF, 0x1_0000, 0x7F_FFFF] {
+            let mut address = Address::new(dlci);
+            address.becn = true;
+
+            let bytes = address.to_bytes();
+            let result = Address::parse(&bytes);
+
+            assert_eq!(bytes.len(), address.len.bytes());
+            assert_eq!(result.unwrap(), address);
+        }
+        assert_eq!(Address::new(1023).len, AddressLen::Two);
+        assert_eq!(Address::new(1024).len, AddressLen::Three);
+        assert_eq!(Address::new(0x1_0000).len, AddressLen::Four);
+        assert_eq!(Address::new(0xABCD).to_bytes(), [0xA8, 0xF0, 0x35])
+    }
+
+    #[test]
+    fn rejects_bad_addresses() {
+        assert_eq!(
+            Address::parse(&[0x19, 0x41]).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0x19 }
+        );
+        assert_eq!(
+            Address::parse(&[0x18, 0x40, 0x00, 0x02, 0x01]).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0x02 }
+        );
+        assert_eq!(
+            Address::parse(&[0x18, 0x40, 0x03]).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0x03 }
+        );
+        assert_eq!(
+            Address::parse(&[0x18, 0x40]).unwrap_err(),
+            HDLCError::MissingControl
+        );
+        assert_eq!(Address::parse(&[]).unwrap_err(), HDLCError::MissingControl)
+    }
+
+    #[test]
+    fn encodes_ansi_status_enquiry() {
+        let enquiry = LmiMessage {
+            kind: LmiMessageKind::StatusEnquiry,
+            report: ReportType::LinkIntegrity,
+            sequence: Some((5, 4)),
+            pvcs: Vec::new(),
+        };
+
+        let bytes = enquiry.to_frame(LmiVariant::AnsiAnnexD);
+        let frame = Frame::parse(&bytes).unwrap();
+        let result = LmiMessage::from_frame(LmiVariant::AnsiAnnexD, &frame);
+
+        assert_eq!(
+            bytes,
+            [0x00, 0x01, 0x03, 0x08, 0x00, 0x75, 0x95, 0x01, 0x01, 0x01, 0x03, 0x02, 0x05, 0x04]
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), enquiry)
+    }
+
+    #[test]
+    fn parses_q933_full_status() {
+        let payload = [
+            0x03, 0x08, 0x00, 0x7D, 0x51, 0x01, 0x00, 0x53, 0x02, 0x07, 0x06, 0x57, 0x03, 0x06,
+            0xA0, 0x82, 0x57, 0x03, 0x0C, 0xC0, 0x88,
+        ];
+
+        let result = LmiMessage::parse(LmiVariant::Q933AnnexA, &payload);
+
+        assert!(result.is_ok());
+        let status = result.unwrap();
+        assert_eq!(
+            status,
+            LmiMessage {
+                kind: LmiMessageKind::Status,
+                report: ReportType::FullStatus,
+                sequence: Some((7, 6)),
+                pvcs: vec![pvc(100, false, true), pvc(200, true, false)],
+            }
+        );
+        assert_eq!(status.to_bytes(LmiVariant::Q933AnnexA), payload);
+        // Without the locking shift ANSI expects
+        assert_eq!(
+            LmiMessage::parse(LmiVariant::AnsiAnnexD, &payload).unwrap_err(),
+            HDLCError::MalformedMessage { protocol: "LMI" }
+        );
+        assert_eq!(
+            LmiMessage::parse(LmiVariant::Q933AnnexA, &payload[..payload.len() - 1]).unwrap_err(),
+            HDLCError::MalformedMessage { protocol: "LMI" }
+        )
+    }
+
+    #[test]
+    fn polls_pvc_status() {
+        let mut poller = LmiPoller::new(3);
+        let status = |report, send, receive, pvcs| LmiMessage {
+            kind: LmiMessageKind::Status,
+            report,
+            sequence: Some((send, receive)),
+            pvcs,
+        };
+
+        let reports: Vec<_> = (0..4).map(|_| poller.poll().report).collect();
+        assert_eq!(
+            reports,
+            [
+                ReportType::FullStatus,
+                ReportType::LinkIntegrity,
+                ReportType::LinkIntegrity,
+                ReportType::FullStatus
+            ]
+        );
+
+        assert!(poller.receive(&status(
+            ReportType::FullStatus,
+            9,
+            4,
+            vec![pvc(100, true, true), pvc(200, true, false)],
+        )));
+        assert_eq!(poller.poll().sequence, Some((5, 9)));
+        assert_eq!(poller.pvcs().count(), 2);
+
+        assert!(poller.receive(&status(
+            ReportType::SinglePvc,
+            10,
+            5,
+            vec![pvc(200, false, true)],
+        )));
+        assert!(poller.pvc(200).unwrap().active);
+
+        assert!(poller.receive(&status(
+            ReportType::FullStatus,
+            11,
+            5,
+            vec![PvcStatus {
+                dlci: 100,
+                new: false,
+                deleted: true,
+                active: false,
+            }],
+        )));
+        assert_eq!(poller.pvcs().count(), 0)
+    }
+
+    #[test]
+    fn ignores_status_with_wrong_receive_sequence() {
+        let mut poller = LmiPoller::default();
+        assert_eq!(poller.poll().sequence, Some((1, 0)));
+
+        let stale = LmiMessage {
+            kind: LmiMessageKind::Status,
+            report: ReportType::FullStatus,
+            sequence: Some((7, 0)),
+            pvcs: vec![pvc(100, true, true)],
+        };
+        assert!(!poller.receive(&stale));
+        assert_eq!(poller.pvcs().count(), 0);
+        assert_eq!(poller.poll().sequence, Some((2, 0)));
+    }
+}
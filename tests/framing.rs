@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use hdlc::{decode, decode_slice, encode, HDLCError, SpecialChars};
+    use hdlc::{
+        abort_sequence, decode, decode_slice, decode_slice_with_options, decode_with_options,
+        encode, encode_into, encode_iter, encode_parts, encode_to_vec, encoded_len, ClosingFend,
+        DecodeOptions, HDLCError, OpeningFend, RuntPolicy, SpecialChars,
+    };
 
     #[test]
     fn packetizes() {
@@ -30,7 +34,7 @@ mod tests {
     fn pack_custom_s_chars() {
         let msg: Vec<u8> = vec![0x01, 0x7E, 0x70, 0x7D, 0x00, 0x05, 0x80, 0x09];
         let cmp: Vec<u8> = vec![0x71, 1, 126, 112, 80, 125, 0, 5, 128, 9, 0x71];
-        let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50);
+        let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50).unwrap();
 
         let result = encode(&msg, chars);
 
@@ -40,10 +44,7 @@ mod tests {
 
     #[test]
     fn pack_rejects_dupe_s_chars() {
-        let chars = SpecialChars::new(0x7E, 0x7D, 0x5D, 0x5D);
-        let msg: Vec<u8> = vec![0x01, chars.fend, 0x00, chars.fesc, 0x00, 0x05, 0x80, 0x09];
-
-        let result = encode(&msg, chars);
+        let result = SpecialChars::new(0x7E, 0x7D, 0x5D, 0x5D);
 
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), HDLCError::DuplicateSpecialChar)
@@ -53,7 +54,16 @@ mod tests {
     fn depacketizes() {
         let chars = SpecialChars::default();
         let msg: Vec<u8> = vec![
-            chars.fend, 0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09, chars.fend,
+            chars.fend(),
+            0x01,
+            0x50,
+            0x00,
+            0x00,
+            0x00,
+            0x05,
+            0x80,
+            0x09,
+            chars.fend(),
         ];
         let cmp: Vec<u8> = vec![1, 80, 0, 0, 0, 5, 128, 9];
 
@@ -67,18 +77,18 @@ mod tests {
     fn depack_it_swaps() {
         let chars = SpecialChars::default();
         let msg: Vec<u8> = vec![
-            chars.fend,
+            chars.fend(),
             0x01,
-            chars.fesc,
-            chars.tfesc,
+            chars.fesc(),
+            chars.tfesc(),
             0x00,
             0x00,
-            chars.fesc,
-            chars.tfend,
+            chars.fesc(),
+            chars.tfend(),
             0x05,
             0x80,
             0x09,
-            chars.fend,
+            chars.fend(),
         ];
         let cmp: Vec<u8> = vec![1, 125, 0, 0, 126, 5, 128, 9];
 
@@ -90,18 +100,18 @@ mod tests {
 
     #[test]
     fn depack_custom_s_chars() {
-        let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50);
+        let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50).unwrap();
         let msg: Vec<u8> = vec![
-            chars.fend,
+            chars.fend(),
             0x01,
             0x7E,
-            chars.fesc,
-            chars.tfend,
+            chars.fesc(),
+            chars.tfend(),
             0x00,
             0x05,
             0x80,
-            chars.fesc,
-            chars.tfesc,
+            chars.fesc(),
+            chars.tfesc(),
             0x09,
             0x71,
         ];
@@ -115,49 +125,88 @@ mod tests {
 
     #[test]
     fn depack_rejects_dupe_s_chars() {
-        let chars = SpecialChars::new(0x7E, 0x7D, 0x5D, 0x5D);
-        let msg: Vec<u8> = vec![0x01, chars.fend, 0x00, chars.fesc, 0x00, 0x05, 0x80, 0x09];
-
-        let result = decode(&msg, chars);
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::DuplicateSpecialChar)
+        let unique = [0x7E, 0x7D, 0x5E, 0x5D];
+        for i in 0..4 {
+            for j in 0..4 {
+                let mut bytes = unique;
+                bytes[j] = bytes[i];
+
+                let result = SpecialChars::new(bytes[0], bytes[1], bytes[2], bytes[3]);
+
+                if i == j {
+                    assert_eq!(result, Ok(SpecialChars::default()));
+                } else {
+                    assert_eq!(result, Err(HDLCError::DuplicateSpecialChar));
+                }
+            }
+        }
     }
 
     #[test]
     fn depack_rejects_stray_fend_char() {
         let chars = SpecialChars::default();
         let msg: Vec<u8> = vec![
-            chars.fend, 0x01, 0x00, 0x69, 0x00, 0x05, 0x80, 0x09, chars.fend, chars.fend,
+            chars.fend(),
+            0x01,
+            0x00,
+            0x69,
+            0x00,
+            0x05,
+            0x80,
+            chars.fend(),
+            0x09,
+            chars.fend(),
         ];
 
         let result = decode(&msg, chars);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::FendCharInData)
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::FendCharInData {
+                offset: 7,
+                frame: 0
+            }
+        )
     }
 
     #[test]
     fn depack_rejects_stray_fesc_char() {
         let chars = SpecialChars::default();
         let msg: Vec<u8> = vec![
-            chars.fend, 0x01, chars.fesc, 0x00, chars.fesc, 0x00, 0x05, 0x80, 0x09, chars.fend,
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            0x00,
+            chars.fesc(),
+            0x00,
+            0x05,
+            0x80,
+            0x09,
+            chars.fend(),
         ];
 
         let result = decode(&msg, chars);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::MissingTradeChar)
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::MissingTradeChar {
+                offset: 3,
+                byte: Some(0x00),
+                frame: 0
+            }
+        )
     }
 
     #[test]
     fn depack_rejects_incomplete_message() {
         let chars = SpecialChars::default();
         let msg: Vec<u8> = vec![
-            chars.fend,
+            chars.fend(),
             0x01,
-            chars.fesc,
-            chars.tfesc,
+            chars.fesc(),
+            chars.tfesc(),
             0x77,
             0x00,
             0x05,
@@ -168,14 +217,29 @@ mod tests {
         let result = decode(&msg, chars);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::MissingFinalFend)
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::MissingFinalFend {
+                offset: 9,
+                frame: 0
+            }
+        )
     }
 
     #[test]
     fn depacketizes_slice() {
         let chars = SpecialChars::default();
         let mut msg = [
-            chars.fend, 0x01, 0x50, 0x00, 0x00, 0x00, 0x05, 0x80, 0x09, chars.fend,
+            chars.fend(),
+            0x01,
+            0x50,
+            0x00,
+            0x00,
+            0x00,
+            0x05,
+            0x80,
+            0x09,
+            chars.fend(),
         ];
         let cmp = [1, 80, 0, 0, 0, 5, 128, 9];
 
@@ -189,18 +253,18 @@ mod tests {
     fn depack_slice_it_swaps() {
         let chars = SpecialChars::default();
         let mut msg = [
-            chars.fend,
+            chars.fend(),
             0x01,
-            chars.fesc,
-            chars.tfesc,
+            chars.fesc(),
+            chars.tfesc(),
             0x00,
             0x00,
-            chars.fesc,
-            chars.tfend,
+            chars.fesc(),
+            chars.tfend(),
             0x05,
             0x80,
             0x09,
-            chars.fend,
+            chars.fend(),
         ];
         let cmp = [1, 125, 0, 0, 126, 5, 128, 9];
 
@@ -212,18 +276,18 @@ mod tests {
 
     #[test]
     fn depack_slice_custom_s_chars() {
-        let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50);
+        let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50).unwrap();
         let mut msg = [
-            chars.fend,
+            chars.fend(),
             0x01,
             0x7E,
-            chars.fesc,
-            chars.tfend,
+            chars.fesc(),
+            chars.tfend(),
             0x00,
             0x05,
             0x80,
-            chars.fesc,
-            chars.tfesc,
+            chars.fesc(),
+            chars.tfesc(),
             0x09,
             0x71,
         ];
@@ -236,50 +300,92 @@ mod tests {
     }
 
     #[test]
-    fn depack_slice_rejects_dupe_s_chars() {
-        let chars = SpecialChars::new(0x7E, 0x7D, 0x5D, 0x5D);
-        let mut msg = [0x01, chars.fend, 0x00, chars.fesc, 0x00, 0x05, 0x80, 0x09];
-
-        let result = decode_slice(&mut msg, chars);
+    fn presets_hold_their_special_chars() {
+        let presets = [
+            (SpecialChars::ISO, [0x7E, 0x7D, 0x5E, 0x5D]),
+            (SpecialChars::DLMS, [0x7E, 0x7D, 0x5E, 0x5D]),
+            (SpecialChars::KISS, [0xC0, 0xDB, 0xDC, 0xDD]),
+            (SpecialChars::SLIP, [0xC0, 0xDB, 0xDC, 0xDD]),
+        ];
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::DuplicateSpecialChar)
+        for (chars, bytes) in presets {
+            assert_eq!(
+                [chars.fend(), chars.fesc(), chars.tfend(), chars.tfesc()],
+                bytes
+            );
+            assert_eq!(
+                SpecialChars::new(bytes[0], bytes[1], bytes[2], bytes[3]),
+                Ok(chars)
+            );
+        }
+        assert_eq!(SpecialChars::default(), SpecialChars::ISO)
     }
 
     #[test]
     fn depack_slice_rejects_stray_fend_char() {
         let chars = SpecialChars::default();
         let mut msg = [
-            chars.fend, 0x01, 0x00, 0x69, 0x00, 0x05, 0x80, 0x09, chars.fend, chars.fend,
+            chars.fend(),
+            0x01,
+            0x00,
+            0x69,
+            0x00,
+            0x05,
+            0x80,
+            chars.fend(),
+            0x09,
+            chars.fend(),
         ];
 
         let result = decode_slice(&mut msg, chars);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::FendCharInData)
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::FendCharInData {
+                offset: 7,
+                frame: 0
+            }
+        )
     }
 
     #[test]
     fn depack_slice_rejects_stray_fesc_char() {
         let chars = SpecialChars::default();
         let mut msg = [
-            chars.fend, 0x01, chars.fesc, 0x00, chars.fesc, 0x00, 0x05, 0x80, 0x09, chars.fend,
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            0x00,
+            chars.fesc(),
+            0x00,
+            0x05,
+            0x80,
+            0x09,
+            chars.fend(),
         ];
 
         let result = decode_slice(&mut msg, chars);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::MissingTradeChar)
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::MissingTradeChar {
+                offset: 3,
+                byte: Some(0x00),
+                frame: 0
+            }
+        )
     }
 
     #[test]
     fn depack_slice_rejects_incomplete_message() {
         let chars = SpecialChars::default();
         let mut msg = [
-            chars.fend,
+            chars.fend(),
             0x01,
-            chars.fesc,
-            chars.tfesc,
+            chars.fesc(),
+            chars.tfesc(),
             0x77,
             0x00,
             0x05,
@@ -290,6 +396,570 @@ mod tests {
         let result = decode_slice(&mut msg, chars);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), HDLCError::MissingFinalFend)
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::MissingFinalFend {
+                offset: 9,
+                frame: 0
+            }
+        )
+    }
+
+    #[test]
+    fn pack_into_slice() {
+        let msg = [0x01, 0x7E, 0x00, 0x7D, 0x00, 0x05, 0x80, 0x09];
+        let cmp = [126, 1, 125, 94, 0, 125, 93, 0, 5, 128, 9, 126];
+        let chars = SpecialChars::default();
+        let mut output = [0u8; 16];
+
+        let result = encode_into(&msg, &mut output, chars);
+
+        assert_eq!(result, Ok(cmp.len()));
+        assert_eq!(output[..cmp.len()], cmp)
+    }
+
+    #[test]
+    fn pack_into_rejects_small_buffer() {
+        let msg = [0x01, 0x7E, 0x00, 0x7D];
+        let chars = SpecialChars::default();
+        let mut output = [0u8; 7];
+
+        let result = encode_into(&msg, &mut output, chars);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), HDLCError::BufferTooSmall)
+    }
+
+    #[test]
+    fn pack_appends_to_vec() {
+        let chars = SpecialChars::default();
+        let mut output = encode(&[0x01], chars).unwrap();
+
+        let result = encode_to_vec(&[0x02, 0x7E], &mut output, chars);
+
+        assert_eq!(result, 5);
+        assert_eq!(output, [126, 1, 126, 126, 2, 125, 94, 126])
+    }
+
+    #[test]
+    fn pack_iter_matches_encode() {
+        let msg: Vec<u8> = vec![0x7E, 0x01, 0x7D, 0x7D, 0x00, 0x7E];
+        let chars = SpecialChars::default();
+
+        let iter = encode_iter(&msg, chars);
+        let (low, high) = iter.size_hint();
+        let result: Vec<u8> = iter.collect();
+
+        assert!(low <= result.len() && result.len() <= high.unwrap());
+        assert_eq!(result, encode(&msg, chars).unwrap());
+        assert_eq!(result.len(), encoded_len(&msg, chars))
+    }
+
+    #[test]
+    fn pack_iter_custom_s_chars() {
+        let chars = SpecialChars::new(0x71, 0x70, 0x51, 0x50).unwrap();
+
+        let result: Vec<_> = encode_iter(&[0x01, 0x71], chars).collect();
+
+        assert_eq!(result, [0x71, 0x01, 0x70, 0x51, 0x71])
+    }
+
+    #[test]
+    fn pack_parts_as_one_frame() {
+        let header: &[u8] = &[0x01, 0x7E];
+        let payload: &[u8] = &[0x00, 0x7D, 0x00, 0x05];
+        let trailer: &[u8] = &[0x80, 0x09];
+        let cmp: Vec<u8> = vec![126, 1, 125, 94, 0, 125, 93, 0, 5, 128, 9, 126];
+        let chars = SpecialChars::default();
+
+        let result = encode_parts(&[header, payload, trailer], chars);
+
+        assert_eq!(result, cmp)
+    }
+
+    #[test]
+    fn pack_io_slices() {
+        use std::io::IoSlice;
+
+        let msg: Vec<u8> = vec![0x01, 0x7E, 0x00, 0x7D];
+        let chars = SpecialChars::default();
+        let parts = [
+            IoSlice::new(&msg[..1]),
+            IoSlice::new(&[]),
+            IoSlice::new(&msg[1..]),
+        ];
+
+        let result = encode_parts(&parts, chars);
+
+        assert_eq!(result, encode(&msg, chars).unwrap())
+    }
+
+    #[test]
+    fn depack_error_shows_position() {
+        let chars = SpecialChars::default();
+        let msg: Vec<u8> = vec![chars.fend(), 0x01, 0x02, chars.fesc(), 0x33, chars.fend()];
+
+        let result = decode(&msg, chars);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Caught a random swap char in the data, followed by 0x33 at byte 4 of frame 0."
+        )
+    }
+
+    #[test]
+    fn depack_rejects_missing_first_fend() {
+        let chars = SpecialChars::default();
+        let msg: Vec<u8> = vec![0x01, 0x02, chars.fend()];
+
+        let result = decode(&msg, chars);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::MissingFirstFend { byte: Some(0x01) }
+        )
+    }
+
+    #[test]
+    fn depack_reports_abort() {
+        let chars = SpecialChars::default();
+        let mut msg: Vec<u8> = vec![chars.fend(), 0x01, 0x02];
+        msg.extend_from_slice(&abort_sequence(chars));
+
+        let result = decode(&msg, chars);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::Aborted {
+                offset: 3,
+                frame: 0
+            }
+        )
+    }
+
+    #[test]
+    fn depack_slice_reports_abort() {
+        let chars = SpecialChars::default();
+        let mut msg = [chars.fend(), 0x01, chars.fesc(), chars.fend()];
+
+        let result = decode_slice(&mut msg, chars);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::Aborted {
+                offset: 2,
+                frame: 0
+            }
+        )
+    }
+
+    #[test]
+    fn depack_accepts_flag_fill() {
+        let chars = SpecialChars::default();
+        let msg: Vec<u8> = vec![
+            chars.fend(),
+            chars.fend(),
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.tfend(),
+            chars.fend(),
+            chars.fend(),
+        ];
+
+        let result = decode(&msg, chars);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), [0x01, chars.fend()])
+    }
+
+    #[test]
+    fn depack_slice_accepts_flag_fill() {
+        let chars = SpecialChars::default();
+        let mut msg = [
+            chars.fend(),
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.tfesc(),
+            0x02,
+            chars.fend(),
+            chars.fend(),
+            chars.fend(),
+        ];
+
+        let result = decode_slice(&mut msg, chars);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), [0x01, chars.fesc(), 0x02])
+    }
+
+    #[test]
+    fn depack_rejects_frame_too_long() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            max_frame_len: Some(3),
+            ..DecodeOptions::default()
+        };
+        let msg: Vec<u8> = vec![
+            chars.fend(),
+            0x01,
+            0x02,
+            chars.fesc(),
+            chars.tfend(),
+            0x03,
+            chars.fend(),
+        ];
+
+        let result = decode_with_options(&msg, chars, options);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::FrameTooLong {
+                offset: 5,
+                max: 3,
+                frame: 0
+            }
+        )
+    }
+
+    #[test]
+    fn depack_slice_rejects_frame_too_long() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            max_frame_len: Some(2),
+            ..DecodeOptions::default()
+        };
+        let mut msg = [chars.fend(), 0x01, 0x02, chars.fesc(), chars.tfend(), 0x03];
+
+        let result = decode_slice_with_options(&mut msg, chars, options);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::FrameTooLong {
+                offset: 3,
+                max: 2,
+                frame: 0
+            }
+        )
+    }
+
+    #[test]
+    fn depack_accepts_frame_at_max_len() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            max_frame_len: Some(3),
+            ..DecodeOptions::default()
+        };
+        let mut msg = [
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.tfesc(),
+            0x03,
+            chars.fend(),
+        ];
+
+        let result = decode_with_options(&msg, chars, options);
+        let slice_result = decode_slice_with_options(&mut msg, chars, options);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), [0x01, chars.fesc(), 0x03]);
+        assert_eq!(slice_result.unwrap(), [0x01, chars.fesc(), 0x03])
+    }
+
+    #[test]
+    fn depack_rejects_runt_frame() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            min_frame_len: 4,
+            ..DecodeOptions::default()
+        };
+        let msg: Vec<u8> = vec![chars.fend(), chars.fend()];
+
+        let result = decode_with_options(&msg, chars, options);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::RuntFrame {
+                offset: 1,
+                len: 0,
+                min: 4,
+                frame: 0
+            }
+        )
+    }
+
+    #[test]
+    fn depack_skips_runt_frame() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            min_frame_len: 2,
+            runts: RuntPolicy::Skip,
+            ..DecodeOptions::default()
+        };
+        let mut msg = [
+            chars.fend(),
+            0x01,
+            chars.fend(),
+            0x02,
+            chars.fesc(),
+            chars.tfend(),
+            chars.fend(),
+        ];
+
+        let result = decode_with_options(&msg, chars, options);
+        let slice_result = decode_slice_with_options(&mut msg, chars, options);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), [0x02, chars.fend()]);
+        assert_eq!(slice_result.unwrap(), [0x02, chars.fend()])
+    }
+
+    #[test]
+    fn depack_slice_rejects_runt_frame() {
+        let chars = SpecialChars::default();
+        let options = DecodeOptions {
+            min_frame_len: 2,
+            ..DecodeOptions::default()
+        };
+        let mut msg = [chars.fend(), 0x01, chars.fend(), 0x02, 0x03, chars.fend()];
+
+        let result = decode_slice_with_options(&mut msg, chars, options);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            HDLCError::RuntFrame {
+                offset: 2,
+                len: 1,
+                min: 2,
+                frame: 0
+            }
+        )
+    }
+
+    #[test]
+    fn depack_slip_without_opening_end() {
+        let chars = SpecialChars::SLIP;
+        let options = DecodeOptions {
+            opening_fend: OpeningFend::Optional,
+            ..DecodeOptions::default()
+        };
+        let msg = [0x45, 0xDB, 0xDC, 0x01, 0xDB, 0xDD, 0xC0];
+        let mut slice = msg;
+        let mut opened = [0xC0, 0xC0, 0x45, 0xC0];
+
+        let result = decode_with_options(&msg, chars, options);
+        let slice_result = decode_slice_with_options(&mut slice, chars, options);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), [0x45, 0xC0, 0x01, 0xDB]);
+        assert_eq!(slice_result.unwrap(), [0x45, 0xC0, 0x01, 0xDB]);
+        assert_eq!(
+            decode_slice_with_options(&mut opened, chars, options).unwrap(),
+            [0x45]
+        );
+        assert_eq!(
+            decode(&msg, chars).unwrap_err(),
+            HDLCError::MissingFirstFend { byte: Some(0x45) }
+        )
+    }
+
+    #[test]
+    fn depack_opening_fend_policies() {
+        let chars = SpecialChars::default();
+        let hunt = DecodeOptions {
+            opening_fend: OpeningFend::Hunt,
+            ..DecodeOptions::default()
+        };
+        let msg = [0x01, chars.fesc(), 0x00, chars.fend(), 0x02, chars.fend()];
+        let mut slice = msg;
+        let mut strict = msg;
+
+        let result = decode_with_options(&msg, chars, hunt);
+        let slice_result = decode_slice_with_options(&mut slice, chars, hunt);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), [0x02]);
+        assert_eq!(slice_result.unwrap(), [0x02]);
+        assert_eq!(
+            decode(&msg, chars).unwrap_err(),
+            HDLCError::MissingFirstFend { byte: Some(0x01) }
+        );
+        assert_eq!(
+            decode_slice(&mut strict, chars).unwrap_err(),
+            HDLCError::MissingFirstFend { byte: Some(0x01) }
+        )
+    }
+
+    #[test]
+    fn depack_closing_fend_and_trailing_policies() {
+        let chars = SpecialChars::default();
+        let open_end = DecodeOptions {
+            closing_fend: ClosingFend::Optional,
+            ..DecodeOptions::default()
+        };
+        let trailing = DecodeOptions {
+            trailing_partial: true,
+            ..DecodeOptions::default()
+        };
+        let unclosed = [chars.fend(), 0x01, chars.fesc(), chars.tfend()];
+        let dangling = [chars.fend(), 0x01, chars.fesc()];
+        let partial = [chars.fend(), 0x01, chars.fend(), 0x02, 0x03];
+
+        assert_eq!(
+            decode_with_options(&unclosed, chars, open_end).unwrap(),
+            [0x01, chars.fend()]
+        );
+        assert_eq!(
+            decode_slice_with_options(&mut unclosed.clone(), chars, open_end).unwrap(),
+            [0x01, chars.fend()]
+        );
+        for result in [
+            decode_with_options(&dangling, chars, open_end).map(|_| ()),
+            decode_slice_with_options(&mut dangling.clone(), chars, open_end).map(|_| ()),
+        ] {
+            assert_eq!(
+                result.unwrap_err(),
+                HDLCError::MissingTradeChar {
+                    offset: 3,
+                    byte: None,
+                    frame: 0
+                }
+            );
+        }
+        assert_eq!(
+            decode_with_options(&partial, chars, trailing).unwrap(),
+            [0x01]
+        );
+        assert_eq!(
+            decode_slice_with_options(&mut partial.clone(), chars, trailing).unwrap(),
+            [0x01]
+        );
+        assert_eq!(
+            decode(&partial, chars).unwrap_err(),
+            HDLCError::FendCharInData {
+                offset: 2,
+                frame: 0
+            }
+        )
+    }
+
+    /// Xorshift generator, so the property tests are repeatable without extra dependencies
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        /// Mostly special characters, so every branch of the decoders is taken
+        fn bytes(&mut self, chars: SpecialChars, max_len: usize) -> Vec<u8> {
+            let len = self.below(max_len + 1);
+            (0..len)
+                .map(|_| match self.below(8) {
+                    0 | 1 => chars.fend(),
+                    2 => chars.fesc(),
+                    3 => chars.tfend(),
+                    4 => chars.tfesc(),
+                    _ => self.next() as u8,
+                })
+                .collect()
+        }
+
+        fn options(&mut self) -> DecodeOptions {
+            DecodeOptions {
+                max_frame_len: [None, Some(0), Some(3), Some(8)][self.below(4)],
+                min_frame_len: self.below(4),
+                runts: [RuntPolicy::Report, RuntPolicy::Skip][self.below(2)],
+                opening_fend: [
+                    OpeningFend::Required,
+                    OpeningFend::Hunt,
+                    OpeningFend::Optional,
+                ][self.below(3)],
+                closing_fend: [ClosingFend::Required, ClosingFend::Optional][self.below(2)],
+                trailing_partial: self.below(2) == 1,
+            }
+        }
+    }
+
+    #[test]
+    fn depack_and_depack_slice_agree() {
+        let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+        for chars in [SpecialChars::default(), SpecialChars::KISS] {
+            for _ in 0..20_000 {
+                let options = rng.options();
+                let input = rng.bytes(chars, 24);
+                let mut slice = input.clone();
+
+                let result = decode_with_options(&input, chars, options);
+                let slice_result =
+                    decode_slice_with_options(&mut slice, chars, options).map(<[u8]>::to_vec);
+
+                assert_eq!(result, slice_result, "{:02X?} with {:?}", input, options);
+            }
+        }
+    }
+
+    #[test]
+    fn pack_then_depack_round_trips() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+        let chars = SpecialChars::default();
+        let hunt = DecodeOptions {
+            opening_fend: OpeningFend::Hunt,
+            ..DecodeOptions::default()
+        };
+        for _ in 0..5_000 {
+            let data = rng.bytes(chars, 32);
+            let garbage: Vec<u8> = rng
+                .bytes(chars, 8)
+                .into_iter()
+                .filter(|&b| b != chars.fend())
+                .collect();
+            let encoded = encode(&data, chars).unwrap();
+            let mut noisy = [&garbage[..], &encoded[..]].concat();
+
+            let result = decode(&encoded, chars);
+            let slice_result = decode_slice(&mut encoded.clone(), chars).map(<[u8]>::to_vec);
+
+            assert_eq!(result.unwrap(), data);
+            assert_eq!(slice_result.unwrap(), data);
+            assert_eq!(
+                decode_slice_with_options(&mut noisy, chars, hunt).unwrap(),
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn depack_long_flag_runs_in_linear_time() {
+        let chars = SpecialChars::default();
+        let fill = vec![chars.fend(); 1 << 20];
+        let msg = [&fill[..], &[0x01, 0x02], &fill[..]].concat();
+        let mut slice = msg.clone();
+        let start = std::time::Instant::now();
+
+        let result = decode(&msg, chars);
+        let slice_result = decode_slice(&mut slice, chars);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), [0x01, 0x02]);
+        assert_eq!(slice_result.unwrap(), [0x01, 0x02]);
+        assert!(start.elapsed() < std::time::Duration::from_secs(1))
     }
 }
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::kiss::{Command, Frame};
+    use hdlc::{decode_frames, encode, HDLCError, SpecialChars};
+
+    #[test]
+    fn encodes_data_frame() {
+        let frame = Frame {
+            port: 2,
+            command: Command::Data(&[0xC0, 0xDB, 0x01]),
+        };
+
+        let result = encode(&frame.to_bytes(), SpecialChars::KISS);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            [0xC0, 0x20, 0xDB, 0xDC, 0xDB, 0xDD, 0x01, 0xC0]
+        )
+    }
+
+    #[test]
+    fn parses_commands() {
+        let stream = [
+            0xC0, 0xC0, 0x01, 0x32, 0xC0, 0x12, 0x3F, 0xC0, 0x03, 0x0A, 0xC0, 0x05, 0x01, 0xC0,
+            0x06, 0xAA, 0xBB, 0xC0, 0xFF, 0xC0,
+        ];
+
+        let frames: Vec<_> = decode_frames(&stream, SpecialChars::KISS)
+            .map(Result::unwrap)
+            .collect();
+        let result: Vec<_> = frames.iter().map(|f| Frame::parse(f).unwrap()).collect();
+
+        assert_eq!(
+            result,
+            [
+                Frame {
+                    port: 0,
+                    command: Command::TxDelay(50)
+                },
+                Frame {
+                    port: 1,
+                    command: Command::Persistence(63)
+                },
+                Frame {
+                    port: 0,
+                    command: Command::SlotTime(10)
+                },
+                Frame {
+                    port: 0,
+                    command: Command::FullDuplex(true)
+                },
+                Frame {
+                    port: 0,
+                    command: Command::SetHardware(&[0xAA, 0xBB])
+                },
+                Frame {
+                    port: 0,
+                    command: Command::Return
+                },
+            ]
+        );
+        for (frame, raw) in result.iter().zip(&frames) {
+            assert_eq!(frame.to_bytes(), raw[..]);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_frames() {
+        let malformed = HDLCError::MalformedMessage { protocol: "KISS" };
+
+        assert_eq!(Frame::parse(&[]).unwrap_err(), malformed);
+        assert_eq!(Frame::parse(&[0x01]).unwrap_err(), malformed);
+        assert_eq!(Frame::parse(&[0x08, 0x00]).unwrap_err(), malformed);
+        assert_eq!(
+            Frame::parse(&[0x30]).unwrap(),
+            Frame {
+                port: 3,
+                command: Command::Data(&[])
+            }
+        )
+    }
+}
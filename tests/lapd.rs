@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use hdlc::frame::{ExtendedControl, Unnumbered};
+    use hdlc::lapd::{
+        Address, Frame, Sapi, Side, Tei, TeiManager, TeiMessage, TeiMessageKind, TeiState,
+    };
+    use hdlc::HDLCError;
+
+    fn network(kind: TeiMessageKind, ri: u16, ai: u8) -> TeiMessage {
+        TeiMessage {
+            kind,
+            ri,
+            ai: Tei::new(ai).unwrap(),
+        }
+    }
+
+    #[test]
+    fn builds_and_parses_i_frame() {
+        let frame = Frame {
+            address: Address::command(Sapi::CALL_CONTROL, Tei::new(64).unwrap(), Side::User),
+            control: ExtendedControl::Information {
+                ns: 127,
+                nr: 3,
+                pf: false,
+            },
+            info: &[0x08, 0x01, 0x05],
+        };
+
+        let bytes = frame.to_bytes();
+        let result = Frame::parse(&bytes);
+
+        assert_eq!(bytes, [0x00, 0x81, 0xFE, 0x06, 0x08, 0x01, 0x05]);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), frame)
+    }
+
+    #[test]
+    fn reads_command_response_bit() {
+        let tei = Tei::new(5).unwrap();
+        let user_command = Address::command(Sapi::X25, tei, Side::User);
+        let network_response = Address::response(Sapi::X25, tei, Side::Network);
+
+        assert_eq!(user_command.to_bytes(), [0x40, 0x0B]);
+        assert_eq!(network_response, user_command);
+        assert!(user_command.is_command(Side::User));
+        assert!(!user_command.is_command(Side::Network));
+        assert!(!tei.is_automatic());
+        assert!(Tei::GROUP.is_group())
+    }
+
+    #[test]
+    fn rejects_bad_addresses() {
+        assert_eq!(
+            Sapi::new(64).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 64 }
+        );
+        assert_eq!(
+            Tei::new(128).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 128 }
+        );
+        assert_eq!(
+            Frame::parse(&[0x01, 0x01, 0x03]).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0x01 }
+        );
+        assert_eq!(
+            Frame::parse(&[0x00, 0x80, 0x03]).unwrap_err(),
+            HDLCError::InvalidAddress { byte: 0x80 }
+        );
+        assert_eq!(
+            Frame::parse(&[0x00, 0x81]).unwrap_err(),
+            HDLCError::MissingControl
+        )
+    }
+
+    #[test]
+    fn encodes_tei_messages() {
+        let request = TeiMessage {
+            kind: TeiMessageKind::IdentityRequest,
+            ri: 0x1234,
+            ai: Tei::GROUP,
+        };
+
+        let bytes = request.to_frame();
+        let frame = Frame::parse(&bytes).unwrap();
+
+        assert_eq!(bytes, [0xFC, 0xFF, 0x03, 0x0F, 0x12, 0x34, 0x01, 0xFF]);
+        assert_eq!(
+            frame.control,
+            ExtendedControl::Unnumbered {
+                kind: Unnumbered::Ui,
+                pf: false
+            }
+        );
+        assert_eq!(TeiMessage::from_frame(&frame).unwrap(), request);
+        assert_eq!(
+            network(TeiMessageKind::IdentityAssigned, 0x1234, 64).to_frame()[..2],
+            [0xFE, 0xFF]
+        );
+        assert_eq!(
+            TeiMessage::parse(&[0x0F, 0x00, 0x00, 0x09, 0xFF]).unwrap_err(),
+            HDLCError::MalformedMessage {
+                protocol: "TEI management"
+            }
+        );
+        // Ai with EA cleared
+        assert_eq!(
+            TeiMessage::parse(&[0x0F, 0x12, 0x34, 0x02, 0x80]).unwrap_err(),
+            HDLCError::MalformedMessage {
+                protocol: "TEI management"
+            }
+        )
+    }
+
+    #[test]
+    fn manages_tei() {
+        let mut manager = TeiManager::new();
+
+        manager.request(0x0101);
+        // Reply to someone else's request
+        manager.receive(&network(TeiMessageKind::IdentityAssigned, 0x0202, 65));
+        assert_eq!(manager.state(), TeiState::Requested { ri: 0x0101 });
+        manager.receive(&network(TeiMessageKind::IdentityAssigned, 0x0101, 64));
+        assert_eq!(manager.tei(), Some(Tei::new(64).unwrap()));
+
+        let check = manager.receive(&network(TeiMessageKind::CheckRequest, 0, 127));
+        assert_eq!(
+            check,
+            Some(TeiMessage {
+                kind: TeiMessageKind::CheckResponse,
+                ri: 0x0101,
+                ai: Tei::new(64).unwrap(),
+            })
+        );
+        assert_eq!(
+            manager.receive(&network(TeiMessageKind::CheckRequest, 0, 65)),
+            None
+        );
+        assert_eq!(
+            manager.verify().map(|m| m.kind),
+            Some(TeiMessageKind::Verify)
+        );
+
+        manager.receive(&network(TeiMessageKind::Remove, 0, 64));
+        assert_eq!(manager.state(), TeiState::Unassigned);
+        assert_eq!(manager.verify(), None)
+    }
+
+    #[test]
+    fn drops_duplicate_tei() {
+        let mut manager = TeiManager::new();
+        manager.request(0x0101);
+        manager.receive(&network(TeiMessageKind::IdentityAssigned, 0x0101, 70));
+
+        manager.receive(&network(TeiMessageKind::IdentityAssigned, 0x0303, 70));
+
+        assert_eq!(manager.state(), TeiState::Unassigned);
+        manager.request(0x0404);
+        manager.receive(&network(TeiMessageKind::IdentityDenied, 0x0404, 127));
+        assert_eq!(manager.state(), TeiState::Unassigned)
+    }
+
+    #[test]
+    fn ignores_non_automatic_tei() {
+        let mut manager = TeiManager::new();
+        manager.request(0x0101);
+
+        manager.receive(&network(TeiMessageKind::IdentityAssigned, 0x0101, 12));
+        assert_eq!(manager.state(), TeiState::Requested { ri: 0x0101 });
+        manager.receive(&network(TeiMessageKind::IdentityAssigned, 0x0101, 127));
+        assert_eq!(manager.state(), TeiState::Requested { ri: 0x0101 });
+
+        manager.receive(&network(TeiMessageKind::IdentityAssigned, 0x0101, 126));
+        assert_eq!(manager.tei(), Some(Tei::new(126).unwrap()))
+    }
+}
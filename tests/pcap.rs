@@ -0,0 +1,211 @@
+#![cfg(feature = "pcap")]
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use hdlc::pcap::{Direction, Error, Format, LinkType, Packet, PcapReader, PcapWriter};
+
+    fn read_all(file: &[u8]) -> Vec<Packet> {
+        PcapReader::new(file)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn writes_and_reads_pcap() {
+        let time = Duration::new(1_700_000_000, 123_456_000);
+        let mut writer = PcapWriter::new(Vec::new(), Format::Pcap, LinkType::CHdlc).unwrap();
+        writer
+            .write_frame(&[0x0F, 0x00, 0x08, 0x00], time, Direction::Inbound)
+            .unwrap();
+        writer
+            .write_frame(&[0x8F], time, Direction::Unknown)
+            .unwrap();
+
+        let file = writer.finish().unwrap();
+        let result = read_all(&file);
+
+        assert_eq!(
+            file[..24],
+            [
+                0xD4, 0xC3, 0xB2, 0xA1, 0x02, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x68, 0x00, 0x00, 0x00
+            ]
+        );
+        assert_eq!(
+            result,
+            [
+                Packet {
+                    timestamp: time,
+                    direction: Direction::Unknown,
+                    link_type: LinkType::CHdlc,
+                    data: vec![0x0F, 0x00, 0x08, 0x00],
+                },
+                Packet {
+                    timestamp: time,
+                    direction: Direction::Unknown,
+                    link_type: LinkType::CHdlc,
+                    data: vec![0x8F],
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn keeps_direction_in_pseudo_header() {
+        let time = Duration::from_secs(10);
+        let mut writer = PcapWriter::new(Vec::new(), Format::Pcap, LinkType::LapbWithDir).unwrap();
+        writer
+            .write_frame(&[0x01, 0x3F], time, Direction::Outbound)
+            .unwrap();
+        writer
+            .write_frame(&[0x03, 0x73], time, Direction::Inbound)
+            .unwrap();
+
+        let file = writer.into_inner();
+        let result = read_all(&file);
+
+        assert_eq!(file[24 + 16..24 + 16 + 3], [0x01, 0x01, 0x3F]);
+        assert_eq!(result[0].direction, Direction::Outbound);
+        assert_eq!(result[0].data, [0x01, 0x3F]);
+        assert_eq!(result[1].direction, Direction::Inbound);
+        assert_eq!(result[1].data, [0x03, 0x73])
+    }
+
+    #[test]
+    fn writes_and_reads_pcapng() {
+        let frames: [&[u8]; 3] = [&[0xFF, 0x03, 0xC0, 0x21, 0x01], &[0xFF, 0x03], &[]];
+        let directions = [Direction::Outbound, Direction::Unknown, Direction::Inbound];
+        let mut writer = PcapWriter::new(Vec::new(), Format::Pcapng, LinkType::PppHdlc).unwrap();
+        for (i, (frame, direction)) in frames.iter().zip(directions).enumerate() {
+            let time = Duration::from_micros(1_700_000_000_000_000 + i as u64);
+            writer.write_frame(frame, time, direction).unwrap();
+        }
+
+        let file = writer.into_inner();
+        let mut reader = PcapReader::new(&file[..]).unwrap();
+        let result = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(file.len() % 4, 0);
+        assert_eq!(reader.link_type(), Some(LinkType::PppHdlc));
+        assert_eq!(result.len(), 3);
+        for (i, packet) in result.iter().enumerate() {
+            assert_eq!(packet.data, frames[i]);
+            assert_eq!(packet.direction, directions[i]);
+            assert_eq!(
+                packet.timestamp,
+                Duration::from_micros(1_700_000_000_000_000 + i as u64)
+            );
+        }
+    }
+
+    #[test]
+    fn reads_big_endian_nanosecond_pcap() {
+        let mut file = vec![0xA1, 0xB2, 0x3C, 0x4D, 0x00, 0x02, 0x00, 0x04];
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&[0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0xCF]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x07]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x03]);
+        file.extend_from_slice(&[0x00, 0x01, 0x3F]);
+
+        let result = read_all(&file);
+
+        assert_eq!(
+            result,
+            [Packet {
+                timestamp: Duration::new(2, 7),
+                direction: Direction::Inbound,
+                link_type: LinkType::LapbWithDir,
+                data: vec![0x01, 0x3F],
+            }]
+        )
+    }
+
+    #[test]
+    fn reads_big_endian_pcapng_with_resolution() {
+        let mut file = Vec::new();
+        // Section header
+        file.extend_from_slice(&[0x0A, 0x0D, 0x0D, 0x0A, 0x00, 0x00, 0x00, 0x1C]);
+        file.extend_from_slice(&[0x1A, 0x2B, 0x3C, 0x4D, 0x00, 0x01, 0x00, 0x00]);
+        file.extend_from_slice(&[0xFF; 8]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x1C]);
+        // Interface description, LAPD, if_tsresol 10^-9
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x20]);
+        file.extend_from_slice(&[0x00, 0xCB, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF]);
+        file.extend_from_slice(&[0x00, 0x09, 0x00, 0x01, 0x09, 0x00, 0x00, 0x00]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20]);
+        // Name resolution block, skipped
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x10]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10]);
+        // Enhanced packet, 1.5 s, outbound
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x30]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        file.extend_from_slice(&[0x59, 0x68, 0x2F, 0x00, 0x00, 0x00, 0x00, 0x03]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x03, 0x02, 0x01, 0x7F, 0x00]);
+        file.extend_from_slice(&[0x00, 0x02, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02]);
+        file.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x30]);
+
+        let result = read_all(&file);
+
+        assert_eq!(
+            result,
+            [Packet {
+                timestamp: Duration::from_millis(1500),
+                direction: Direction::Outbound,
+                link_type: LinkType::Lapd,
+                data: vec![0x02, 0x01, 0x7F],
+            }]
+        )
+    }
+
+    #[test]
+    fn rejects_bad_captures() {
+        let bad_magic = PcapReader::new(&b"7E 01 7E\n"[..]);
+
+        let mut file = PcapWriter::new(Vec::new(), Format::Pcap, LinkType::CHdlc)
+            .unwrap()
+            .into_inner();
+        file.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 4, 0, 0, 0, 0x0F]);
+        let mut reader = PcapReader::new(&file[..]).unwrap();
+
+        assert!(matches!(bad_magic, Err(Error::BadMagic(0x3745_2030))));
+        assert!(matches!(reader.next(), Some(Err(Error::Truncated))));
+        assert!(reader.next().is_none())
+    }
+
+    #[test]
+    fn rejects_oversized_lengths() {
+        let mut pcap = PcapWriter::new(Vec::new(), Format::Pcap, LinkType::CHdlc)
+            .unwrap()
+            .into_inner();
+        pcap.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0xF0, 0xFF, 0xFF, 0xFF, 4, 0, 0, 0]);
+        let mut pcapng = PcapWriter::new(Vec::new(), Format::Pcapng, LinkType::CHdlc)
+            .unwrap()
+            .into_inner();
+        pcapng.extend_from_slice(&[6, 0, 0, 0, 0xFC, 0xFF, 0xFF, 0xFF]);
+        let section = [
+            0x0A, 0x0D, 0x0D, 0x0A, 0xFC, 0xFF, 0xFF, 0xFF, 0x4D, 0x3C, 0x2B, 0x1A,
+        ];
+
+        let record = PcapReader::new(&pcap[..]).unwrap().next();
+        let block = PcapReader::new(&pcapng[..]).unwrap().next();
+        let header = PcapReader::new(&section[..]);
+
+        assert!(matches!(record, Some(Err(Error::Malformed(_)))));
+        assert!(matches!(block, Some(Err(Error::Malformed(_)))));
+        assert!(matches!(header, Err(Error::Malformed(_))))
+    }
+
+    #[test]
+    fn rejects_timestamp_past_2106() {
+        let late = Duration::from_secs(u64::from(u32::MAX) + 1);
+        let mut writer = PcapWriter::new(Vec::new(), Format::Pcap, LinkType::CHdlc).unwrap();
+
+        let result = writer.write_frame(&[0x0F], late, Direction::Unknown);
+
+        assert!(matches!(result, Err(Error::TimestampOutOfRange(t)) if t == late));
+        assert_eq!(writer.into_inner().len(), 24)
+    }
+}
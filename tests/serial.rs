@@ -0,0 +1,204 @@
+#![cfg(feature = "embedded-io")]
+
+#[cfg(test)]
+mod tests {
+    use hdlc::serial::{Error, FrameReader, FrameWriter};
+    use hdlc::{DecodeOptions, HDLCError, OpeningFend, RuntPolicy, SpecialChars};
+
+    #[test]
+    fn writes_frame() {
+        let msg = [0x01, 0x7E, 0x00, 0x7D, 0x00, 0x05, 0x80, 0x09];
+        let cmp = [126, 1, 125, 94, 0, 125, 93, 0, 5, 128, 9, 126];
+        let mut wire = [0u8; 12];
+
+        let mut writer = FrameWriter::new(&mut wire[..], SpecialChars::default());
+        let result = writer.write_frame(&msg);
+
+        assert!(result.is_ok());
+        assert_eq!(wire, cmp)
+    }
+
+    #[test]
+    fn refuses_payload_over_max_frame_len() {
+        let mut wire = [0u8; 8];
+
+        let mut writer =
+            FrameWriter::new(&mut wire[..], SpecialChars::default()).with_max_frame_len(2);
+        let result = writer.write_frame(&[0x01, 0x02, 0x03]);
+        let fits = writer.write_frame(&[0x01, 0x02]);
+
+        assert_eq!(
+            result,
+            Err(Error::Hdlc(HDLCError::PayloadTooLong { len: 3, max: 2 }))
+        );
+        assert!(fits.is_ok());
+        assert_eq!(wire[..4], [0x7E, 0x01, 0x02, 0x7E])
+    }
+
+    #[test]
+    fn reads_frames_after_garbage() {
+        let chars = SpecialChars::default();
+        let wire = [
+            0x33,
+            0x44,
+            chars.fend(),
+            0x01,
+            chars.fesc(),
+            chars.tfend(),
+            chars.fend(),
+            chars.fend(),
+            0x02,
+            chars.fend(),
+        ];
+        let mut reader = FrameReader::new(&wire[..], chars);
+        let mut hunting = FrameReader::new(&wire[..], chars).with_options(DecodeOptions {
+            opening_fend: OpeningFend::Hunt,
+            ..DecodeOptions::default()
+        });
+        let mut buf = [0u8; 8];
+
+        assert_eq!(
+            reader.read_frame(&mut buf),
+            Err(Error::Hdlc(HDLCError::MissingFirstFend {
+                byte: Some(0x33)
+            }))
+        );
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x01, 0x7E]);
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x02]);
+        assert_eq!(reader.read_frame(&mut buf), Err(Error::UnexpectedEof));
+        assert_eq!(hunting.read_frame(&mut buf).unwrap(), [0x01, 0x7E])
+    }
+
+    #[test]
+    fn reads_shared_flag_frames() {
+        let chars = SpecialChars::default();
+        let wire = [chars.fend(), 0x01, chars.fend(), 0x02, 0x03, chars.fend()];
+        let mut reader = FrameReader::new(&wire[..], chars);
+        let mut buf = [0u8; 8];
+
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x01]);
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x02, 0x03])
+    }
+
+    #[test]
+    fn read_overflow_resyncs() {
+        let chars = SpecialChars::default();
+        let wire = [
+            chars.fend(),
+            0x01,
+            0x02,
+            0x03,
+            0x04,
+            0x05,
+            chars.fend(),
+            chars.fend(),
+            0x06,
+            chars.fend(),
+        ];
+        let mut reader = FrameReader::new(&wire[..], chars);
+        let mut buf = [0u8; 4];
+
+        assert_eq!(reader.read_frame(&mut buf), Err(Error::Overflow));
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x06])
+    }
+
+    #[test]
+    fn read_reports_bad_frame() {
+        let chars = SpecialChars::default();
+        let wire = [chars.fend(), 0x01, chars.fesc(), 0x00, chars.fend()];
+        let mut reader = FrameReader::new(&wire[..], chars);
+        let mut buf = [0u8; 8];
+
+        assert_eq!(
+            reader.read_frame(&mut buf),
+            Err(Error::Hdlc(HDLCError::MissingTradeChar {
+                offset: 3,
+                byte: Some(0x00),
+                frame: 0
+            }))
+        )
+    }
+
+    #[test]
+    fn abort_drops_frame() {
+        let chars = SpecialChars::default();
+        let mut wire = [0u8; 8];
+        let mut writer = FrameWriter::new(&mut wire[..], chars);
+        writer.write_frame(&[0x01]).unwrap();
+        writer.write_abort().unwrap();
+        writer.write_frame(&[0x02]).unwrap();
+
+        let mut reader = FrameReader::new(&wire[..], chars);
+        let mut buf = [0u8; 8];
+
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x01]);
+        assert_eq!(
+            reader.read_frame(&mut buf),
+            Err(Error::Hdlc(HDLCError::Aborted {
+                offset: 1,
+                frame: 0
+            }))
+        );
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x02])
+    }
+
+    #[test]
+    fn read_skips_runts() {
+        let chars = SpecialChars::default();
+        let wire = [chars.fend(), 0x01, chars.fend(), 0x02, 0x03, chars.fend()];
+        let options = DecodeOptions {
+            min_frame_len: 2,
+            runts: RuntPolicy::Skip,
+            ..DecodeOptions::default()
+        };
+        let mut reader = FrameReader::new(&wire[..], chars).with_options(options);
+        let mut buf = [0u8; 8];
+
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x02, 0x03]);
+        assert_eq!(reader.read_frame(&mut buf), Err(Error::UnexpectedEof))
+    }
+
+    #[test]
+    fn read_slip_without_opening_end() {
+        let chars = SpecialChars::SLIP;
+        let wire = [0x01, 0xDB, 0xDC, 0xC0, 0x02, 0xC0];
+        let options = DecodeOptions {
+            opening_fend: OpeningFend::Optional,
+            ..DecodeOptions::default()
+        };
+        let mut reader = FrameReader::new(&wire[..], chars).with_options(options);
+        let mut buf = [0u8; 8];
+
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x01, 0xC0]);
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), [0x02])
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    #[test]
+    fn async_round_trip() {
+        use hdlc::serial::asynch;
+        use std::future::Future;
+        use std::pin::pin;
+        use std::task::{Context, Poll, Waker};
+
+        // Slices never return Pending, so a single poll drives each future to completion
+        fn block_on<F: Future>(fut: F) -> F::Output {
+            let mut cx = Context::from_waker(Waker::noop());
+            match pin!(fut).poll(&mut cx) {
+                Poll::Ready(out) => out,
+                Poll::Pending => panic!("slice I/O should never be pending"),
+            }
+        }
+
+        let msg = [0x01, 0x7E, 0x7D, 0x02];
+        let mut wire = [0u8; 8];
+        let mut writer = asynch::FrameWriter::new(&mut wire[..], SpecialChars::default());
+        block_on(writer.write_frame(&msg)).unwrap();
+
+        let mut reader = asynch::FrameReader::new(&wire[..], SpecialChars::default());
+        let mut buf = [0u8; 8];
+        let result = block_on(reader.read_frame(&mut buf));
+
+        assert_eq!(result.unwrap(), msg)
+    }
+}